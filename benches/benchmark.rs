@@ -1,7 +1,10 @@
 #![allow(clippy::disallowed_methods)]
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use overpunch_ng::{convert_from_signed_format, convert_to_signed_format, extract, format};
+use overpunch_ng::{
+    convert_from_signed_format, convert_to_signed_format, extract, extract_with_encoding, format,
+    EncodingTable, TableEncoding,
+};
 use rust_decimal::Decimal;
 use std::str::FromStr;
 
@@ -13,6 +16,20 @@ fn bench_extract(c: &mut Criterion) {
     });
 }
 
+fn bench_extract_table(c: &mut Criterion) {
+    let encoding = TableEncoding::new(EncodingTable::default()).unwrap();
+    c.bench_function("extract (table)", |b| {
+        b.iter(|| {
+            black_box(extract_with_encoding(
+                black_box("1234567G"),
+                black_box(3),
+                &encoding,
+            ))
+            .unwrap();
+        })
+    });
+}
+
 fn bench_format(c: &mut Criterion) {
     let val = Decimal::from_str("1234.567").unwrap();
     c.bench_function("format (ebcdic)", |b| {
@@ -50,6 +67,7 @@ fn bench_convert_to(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_extract,
+    bench_extract_table,
     bench_format,
     bench_convert_from,
     bench_convert_to,
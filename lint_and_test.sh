@@ -36,7 +36,7 @@ fi
 
 # Run clippy
 echo -e "\n${BLUE}${BOLD}Running Clippy lints...${NC}"
-if cargo clippy --all-targets --all-features -- -D warnings; then
+if cargo clippy --workspace --all-targets --all-features -- -D warnings; then
     echo -e "${GREEN}✓ Clippy check passed${NC}"
 else
     echo -e "${RED}✗ Clippy check failed. Please fix the warnings above.${NC}"
@@ -45,7 +45,7 @@ fi
 
 # Run tests
 echo -e "\n${BLUE}${BOLD}Running unit and integration tests...${NC}"
-if RUST_BACKTRACE=1 cargo test; then
+if RUST_BACKTRACE=1 cargo test --workspace; then
     echo -e "${GREEN}✓ All tests passed${NC}"
 else
     echo -e "${RED}✗ Some tests failed. Please fix the failing tests above.${NC}"
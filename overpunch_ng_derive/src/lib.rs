@@ -0,0 +1,178 @@
+use overpunch_ng::{Picture, Usage};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitInt, LitStr};
+
+/// Implements `overpunch_ng::record::OverpunchRecord` for a struct whose fields are annotated
+/// with `#[field(pic = "S9(7)V99", offset = 10)]`.
+///
+/// Numeric pictures map to `rust_decimal::Decimal` fields and alphanumeric pictures to
+/// `String` fields. Pictures are parsed and offsets checked for overlaps at compile time.
+#[proc_macro_derive(OverpunchRecord, attributes(field))]
+pub fn derive_overpunch_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct RecordField {
+    ident: Ident,
+    pic: LitStr,
+    picture: Picture,
+    offset: usize,
+    offset_literal: LitInt,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "OverpunchRecord needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "OverpunchRecord can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut fields = Vec::new();
+    for field in named {
+        let ident = field.ident.clone().expect("named fields have identifiers");
+        fields.push(parse_field(ident, &field.attrs)?);
+    }
+    check_overlaps(&fields)?;
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let names: Vec<String> = fields.iter().map(|f| f.ident.to_string()).collect();
+    let offsets = fields.iter().map(|f| f.offset);
+    let pics = fields.iter().map(|f| &f.pic);
+    let idents: Vec<&Ident> = fields.iter().map(|f| &f.ident).collect();
+
+    let getters = fields.iter().zip(&names).map(|(field, name)| {
+        if field.picture.is_numeric() {
+            quote!(values.number(#name)?)
+        } else {
+            quote!(::std::borrow::ToOwned::to_owned(values.text(#name)?))
+        }
+    });
+    let values = fields.iter().map(|field| {
+        let ident = &field.ident;
+        if field.picture.is_numeric() {
+            quote!(::overpunch_ng::record::FieldValue::Number(self.#ident))
+        } else {
+            quote!(::overpunch_ng::record::FieldValue::Text(
+                ::std::clone::Clone::clone(&self.#ident)
+            ))
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::overpunch_ng::record::OverpunchRecord for #name #type_generics
+        #where_clause
+        {
+            fn layout() -> &'static ::overpunch_ng::copybook::RecordLayout {
+                static LAYOUT: ::std::sync::OnceLock<::overpunch_ng::copybook::RecordLayout> =
+                    ::std::sync::OnceLock::new();
+                LAYOUT.get_or_init(|| {
+                    ::overpunch_ng::copybook::RecordLayout::new(::std::vec![
+                        #(::overpunch_ng::copybook::Field::new(
+                            #names,
+                            #offsets,
+                            ::overpunch_ng::Picture::parse(#pics)
+                                .expect("picture was validated at compile time"),
+                        ),)*
+                    ])
+                })
+            }
+
+            fn from_values(
+                values: &::overpunch_ng::record::Record,
+            ) -> ::std::result::Result<Self, ::overpunch_ng::Error> {
+                ::std::result::Result::Ok(Self {
+                    #(#idents: #getters,)*
+                })
+            }
+
+            fn to_values(&self) -> ::overpunch_ng::record::Record {
+                ::std::iter::FromIterator::from_iter([
+                    #((#names, #values),)*
+                ])
+            }
+        }
+    })
+}
+
+fn parse_field(ident: Ident, attrs: &[syn::Attribute]) -> syn::Result<RecordField> {
+    let attr = match attrs.iter().find(|attr| attr.path().is_ident("field")) {
+        Some(attr) => attr,
+        None => {
+            return Err(Error::new_spanned(
+                &ident,
+                "missing #[field(pic = \"...\", offset = N)] attribute",
+            ))
+        }
+    };
+
+    let mut pic: Option<LitStr> = None;
+    let mut offset: Option<LitInt> = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("pic") {
+            pic = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("offset") {
+            offset = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `pic` or `offset`"))
+        }
+    })?;
+
+    let pic = pic.ok_or_else(|| Error::new_spanned(attr, "missing `pic = \"...\"`"))?;
+    let offset_literal = offset.ok_or_else(|| Error::new_spanned(attr, "missing `offset = N`"))?;
+    let offset = offset_literal.base10_parse()?;
+
+    let picture = Picture::parse(&pic.value()).map_err(|e| Error::new(pic.span(), e))?;
+    if picture.usage() != Usage::Display {
+        return Err(Error::new(
+            pic.span(),
+            format!("usage {} is not supported in text records", picture.usage()),
+        ));
+    }
+
+    Ok(RecordField {
+        ident,
+        pic,
+        picture,
+        offset,
+        offset_literal,
+    })
+}
+
+fn check_overlaps(fields: &[RecordField]) -> syn::Result<()> {
+    let mut ordered: Vec<&RecordField> = fields.iter().collect();
+    ordered.sort_by_key(|field| field.offset);
+    for pair in ordered.windows(2) {
+        let (before, after) = (pair[0], pair[1]);
+        let end = before.offset + before.picture.width();
+        if after.offset < end {
+            return Err(Error::new(
+                after.offset_literal.span(),
+                format!(
+                    "field `{}` at offset {} overlaps `{}`, which occupies offsets {}..{}",
+                    after.ident, after.offset, before.ident, before.offset, end
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
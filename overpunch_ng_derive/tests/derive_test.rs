@@ -0,0 +1,72 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::record::OverpunchRecord;
+use overpunch_ng_derive::OverpunchRecord;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+#[derive(Debug, PartialEq, OverpunchRecord)]
+struct Transaction {
+    #[field(pic = "X(6)", offset = 0)]
+    account: String,
+    #[field(pic = "S9(7)V99", offset = 10)]
+    amount: Decimal,
+    #[field(pic = "9(3)", offset = 6)]
+    branch: Decimal,
+}
+
+#[test]
+fn test_from_record() {
+    let transaction = Transaction::from_record("ACC001042 00001234J").unwrap();
+    assert_eq!(
+        transaction,
+        Transaction {
+            account: "ACC001".to_string(),
+            amount: dec("-123.41"),
+            branch: dec("42"),
+        }
+    );
+}
+
+#[test]
+fn test_to_record() {
+    let transaction = Transaction {
+        account: "AC1".to_string(),
+        amount: dec("99.5"),
+        branch: dec("7"),
+    };
+    let record = transaction.to_record().unwrap();
+    assert_eq!(record, "AC1   00G 00000995{");
+
+    // Alphanumeric fields come back space padded
+    let decoded = Transaction::from_record(&record).unwrap();
+    assert_eq!(decoded.account, "AC1   ");
+    assert_eq!((decoded.amount, decoded.branch), (dec("99.50"), dec("7")));
+    assert_eq!(Transaction::layout().record_length(), 19);
+}
+
+#[test]
+fn test_errors_name_the_field() {
+    assert!(matches!(
+        Transaction::from_record("ACC001042 0000123XJ"),
+        Err(Error::FieldError { field, offset: 10, .. }) if field == "amount"
+    ));
+    assert_eq!(
+        Transaction::from_record("ACC001"),
+        Err(Error::RecordTooShort {
+            required: 19,
+            actual: 6
+        })
+    );
+}
+
+#[test]
+fn test_compile_time_validation() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
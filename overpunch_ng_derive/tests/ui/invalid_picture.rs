@@ -0,0 +1,10 @@
+use overpunch_ng_derive::OverpunchRecord;
+use rust_decimal::Decimal;
+
+#[derive(OverpunchRecord)]
+struct Invalid {
+    #[field(pic = "S9(3)Q", offset = 0)]
+    amount: Decimal,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use overpunch_ng_derive::OverpunchRecord;
+
+#[derive(OverpunchRecord)]
+struct Missing {
+    #[field(pic = "X(5)", offset = 0)]
+    name: String,
+    note: String,
+}
+
+fn main() {}
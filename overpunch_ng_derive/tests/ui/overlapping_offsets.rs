@@ -0,0 +1,12 @@
+use overpunch_ng_derive::OverpunchRecord;
+use rust_decimal::Decimal;
+
+#[derive(OverpunchRecord)]
+struct Overlapping {
+    #[field(pic = "X(5)", offset = 0)]
+    name: String,
+    #[field(pic = "S9(3)", offset = 4)]
+    amount: Decimal,
+}
+
+fn main() {}
@@ -0,0 +1,62 @@
+use crate::core;
+use crate::encoding::{Ebcdic, Encoding, Sign};
+use crate::error::Error;
+use crate::options::SignPosition;
+use num_bigint::{BigInt, BigUint, Sign as BigSign};
+
+/// An integer coefficient and the number of implied decimal places, for fields wider than a
+/// `Decimal` can represent.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ScaledBigInt {
+    pub mantissa: BigInt,
+    pub scale: usize,
+}
+
+pub fn extract_big(raw: &str, decimals: usize) -> Result<ScaledBigInt, Error> {
+    extract_big_with_encoding(raw, decimals, &Ebcdic)
+}
+
+pub fn format_big(value: &ScaledBigInt) -> Result<String, Error> {
+    format_big_with_encoding(value, &Ebcdic)
+}
+
+pub fn extract_big_with_encoding<E: Encoding + ?Sized>(
+    raw: &str,
+    decimals: usize,
+    encoding: &E,
+) -> Result<ScaledBigInt, Error> {
+    let mut digits = Vec::with_capacity(raw.len());
+    let sign = core::scan_digits(
+        raw.chars(),
+        encoding,
+        SignPosition::TrailingOverpunch,
+        |digit| {
+            digits.push(digit);
+            Ok(())
+        },
+    )?;
+
+    let magnitude = BigUint::from_radix_be(&digits, 10).unwrap_or_default();
+    let big_sign = match sign {
+        Sign::Negative => BigSign::Minus,
+        Sign::Positive | Sign::Unsigned => BigSign::Plus,
+    };
+
+    Ok(ScaledBigInt {
+        mantissa: BigInt::from_biguint(big_sign, magnitude),
+        scale: decimals,
+    })
+}
+
+pub fn format_big_with_encoding<E: Encoding + ?Sized>(
+    value: &ScaledBigInt,
+    encoding: &E,
+) -> Result<String, Error> {
+    let sign = match value.mantissa.sign() {
+        BigSign::Minus => Sign::Negative,
+        _ => Sign::Positive,
+    };
+    let coefficient = value.mantissa.magnitude().to_str_radix(10);
+    let digits = core::pad_digits(&coefficient, value.scale, value.scale);
+    core::apply_sign(digits, sign, encoding, SignPosition::TrailingOverpunch)
+}
@@ -0,0 +1,555 @@
+//! `overpunch`: converts fixed-width mainframe extracts to CSV or JSON Lines and back, and
+//! shows how a record layout lines up with the data.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use overpunch_ng::copybook::{Field, RecordLayout};
+use overpunch_ng::dataset::{RecordFormat, RecordReader};
+use overpunch_ng::record::{FieldValue, Record, RecordDecoder, RecordEncoder};
+use overpunch_ng::Picture;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "overpunch", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert fixed-width records to CSV or JSON Lines
+    Decode {
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        data: DataArgs,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = TableFormat::Csv)]
+        to: TableFormat,
+        /// Input file, or standard input when omitted
+        input: Option<PathBuf>,
+        /// Output file, or standard output when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Convert CSV or JSON Lines back to fixed-width records
+    Encode {
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        data: DataArgs,
+        /// Input format
+        #[arg(long, value_enum, default_value_t = TableFormat::Csv)]
+        from: TableFormat,
+        /// Input file, or standard input when omitted
+        input: Option<PathBuf>,
+        /// Output file, or standard output when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show the field layout and how the first records decode, field by field
+    Inspect {
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        data: DataArgs,
+        /// Number of records to show
+        #[arg(short = 'n', long, default_value_t = 5)]
+        records: usize,
+        /// Input file; only the layout is shown when omitted
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct LayoutArgs {
+    /// Copybook describing the record
+    #[arg(short, long)]
+    copybook: Option<PathBuf>,
+    /// Fields laid out back to back, e.g. "ID:9(6),NAME:X(20),AMOUNT:S9(5)V99"
+    #[arg(short, long)]
+    fields: Option<String>,
+}
+
+#[derive(Args)]
+struct DataArgs {
+    /// Records are raw EBCDIC bytes (code page 037) rather than lines of text
+    #[arg(long)]
+    ebcdic: bool,
+    /// Record format of EBCDIC data
+    #[arg(long, value_enum, default_value_t = Recfm::F, requires = "ebcdic")]
+    recfm: Recfm,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum TableFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Recfm {
+    #[value(alias = "fb")]
+    F,
+    V,
+    Vb,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("overpunch: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> CliResult<()> {
+    match cli.command {
+        Command::Decode {
+            layout,
+            data,
+            to,
+            input,
+            output,
+        } => {
+            let decoder = RecordDecoder::new(layout.load()?);
+            let mut writer = TableWriter::new(to, decoder.layout(), create(output.as_deref())?)?;
+            let mut records = Records::open(&data, decoder.layout(), input.as_deref())?;
+            while let Some((number, raw)) = records.next()? {
+                let record = match raw {
+                    Raw::Text(line) => decoder.decode_str(line),
+                    Raw::Bytes(bytes) => decoder.decode_bytes(bytes),
+                }
+                .map_err(|e| format!("record {number}: {e}"))?;
+                writer.write(&record)?;
+            }
+            writer.finish()
+        }
+        Command::Encode {
+            layout,
+            data,
+            from,
+            input,
+            output,
+        } => {
+            let encoder = RecordEncoder::new(layout.load()?);
+            let mut out = create(output.as_deref())?;
+            let mut emit = |number: usize, record: Record| -> CliResult<()> {
+                let encode = |e| format!("record {number}: {e}");
+                if data.ebcdic {
+                    let bytes = encoder.encode_bytes(&record).map_err(encode)?;
+                    write_record(&mut out, data.recfm, &bytes)
+                } else {
+                    let line = encoder.encode_str(&record).map_err(encode)?;
+                    writeln!(out, "{line}").map_err(Into::into)
+                }
+            };
+            let reader = open(input.as_deref())?;
+            match from {
+                TableFormat::Csv => read_csv(reader, encoder.layout(), &mut emit)?,
+                TableFormat::Jsonl => read_jsonl(reader, encoder.layout(), &mut emit)?,
+            }
+            out.flush()?;
+            Ok(())
+        }
+        Command::Inspect {
+            layout,
+            data,
+            records,
+            input,
+        } => {
+            let layout = layout.load()?;
+            print_layout(&layout);
+            if let Some(input) = input {
+                inspect_records(&layout, &data, &input, records)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl LayoutArgs {
+    fn load(&self) -> CliResult<RecordLayout> {
+        if let Some(path) = &self.copybook {
+            let copybook = fs::read_to_string(path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            return Ok(RecordLayout::parse(&copybook)?);
+        }
+        let list = self.fields.as_deref().unwrap_or_default();
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, pic) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("field `{entry}` should look like NAME:PICTURE"))?;
+            let picture =
+                Picture::parse(pic).map_err(|e| format!("field `{}`: {e}", name.trim()))?;
+            let field = Field::new(name.trim(), offset, picture);
+            offset += field.length();
+            fields.push(field);
+        }
+        if fields.is_empty() {
+            return Err("the field list is empty".into());
+        }
+        Ok(RecordLayout::new(fields))
+    }
+}
+
+// The fields a decoder produces values for, which become the CSV columns and JSON keys
+fn columns(layout: &RecordLayout) -> impl Iterator<Item = &Field> {
+    layout
+        .fields()
+        .iter()
+        .filter(|field| !field.is_filler() && !field.redefines)
+}
+
+fn open(path: Option<&Path>) -> CliResult<Box<dyn Read>> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            let file =
+                File::open(path).map_err(|e| format!("cannot open {}: {e}", path.display()))?;
+            Ok(Box::new(file))
+        }
+        _ => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+fn create(path: Option<&Path>) -> CliResult<Box<dyn Write>> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            let file =
+                File::create(path).map_err(|e| format!("cannot create {}: {e}", path.display()))?;
+            Ok(Box::new(BufWriter::new(file)))
+        }
+        _ => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+    }
+}
+
+enum Raw<'a> {
+    Text(&'a str),
+    Bytes(&'a [u8]),
+}
+
+// Numbered records from either newline-separated text or an EBCDIC dataset
+enum Records {
+    Lines {
+        reader: BufReader<Box<dyn Read>>,
+        line: String,
+        number: usize,
+    },
+    Dataset {
+        reader: RecordReader<Box<dyn Read>>,
+        number: usize,
+    },
+}
+
+impl Records {
+    fn open(data: &DataArgs, layout: &RecordLayout, path: Option<&Path>) -> CliResult<Records> {
+        let input = open(path)?;
+        if !data.ebcdic {
+            return Ok(Records::Lines {
+                reader: BufReader::new(input),
+                line: String::new(),
+                number: 0,
+            });
+        }
+        let format = match data.recfm {
+            Recfm::F => RecordFormat::Fixed(layout.record_length()),
+            Recfm::V => RecordFormat::Variable,
+            Recfm::Vb => RecordFormat::VariableBlocked,
+        };
+        Ok(Records::Dataset {
+            reader: RecordReader::new(input, format),
+            number: 0,
+        })
+    }
+
+    fn next(&mut self) -> CliResult<Option<(usize, Raw<'_>)>> {
+        match self {
+            Records::Lines {
+                reader,
+                line,
+                number,
+            } => {
+                line.clear();
+                if reader.read_line(line)? == 0 {
+                    return Ok(None);
+                }
+                *number += 1;
+                let text = line.trim_end_matches(['\n', '\r']);
+                Ok(Some((*number, Raw::Text(text))))
+            }
+            Records::Dataset { reader, number } => {
+                let record = reader
+                    .next_record()
+                    .map_err(|e| format!("record {}: {e}", *number + 1))?;
+                *number += 1;
+                Ok(record.map(|bytes| (*number, Raw::Bytes(bytes))))
+            }
+        }
+    }
+}
+
+fn write_record(out: &mut dyn Write, recfm: Recfm, bytes: &[u8]) -> CliResult<()> {
+    let descriptor = |length: usize| -> CliResult<[u8; 4]> {
+        let length = u16::try_from(length).map_err(|_| "record is too long for RECFM=V")?;
+        let [high, low] = length.to_be_bytes();
+        Ok([high, low, 0, 0])
+    };
+    // Variable blocked output holds one record per block
+    match recfm {
+        Recfm::F => {}
+        Recfm::V => out.write_all(&descriptor(bytes.len() + 4)?)?,
+        Recfm::Vb => {
+            out.write_all(&descriptor(bytes.len() + 8)?)?;
+            out.write_all(&descriptor(bytes.len() + 4)?)?;
+        }
+    }
+    out.write_all(bytes)?;
+    Ok(())
+}
+
+enum TableWriter<'a> {
+    Csv {
+        writer: Box<csv::Writer<Box<dyn Write>>>,
+        layout: &'a RecordLayout,
+    },
+    Jsonl {
+        out: Box<dyn Write>,
+        layout: &'a RecordLayout,
+    },
+}
+
+impl<'a> TableWriter<'a> {
+    fn new(
+        format: TableFormat,
+        layout: &'a RecordLayout,
+        out: Box<dyn Write>,
+    ) -> CliResult<TableWriter<'a>> {
+        match format {
+            TableFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(out);
+                writer.write_record(columns(layout).map(|field| field.name.as_str()))?;
+                Ok(TableWriter::Csv {
+                    writer: Box::new(writer),
+                    layout,
+                })
+            }
+            TableFormat::Jsonl => Ok(TableWriter::Jsonl { out, layout }),
+        }
+    }
+
+    fn write(&mut self, record: &Record) -> CliResult<()> {
+        match self {
+            TableWriter::Csv { writer, layout } => {
+                let cells = columns(layout).map(|field| match record.get(&field.name) {
+                    Some(FieldValue::Number(number)) => number.to_string(),
+                    Some(FieldValue::Text(text)) => text.trim_end().to_string(),
+                    None => String::new(),
+                });
+                writer.write_record(cells)?;
+            }
+            TableWriter::Jsonl { out, layout } => {
+                // Written by hand to keep layout order and every digit of each number
+                let mut line = String::from("{");
+                for (index, field) in columns(layout).enumerate() {
+                    if index > 0 {
+                        line.push(',');
+                    }
+                    line.push_str(&serde_json::to_string(&field.name)?);
+                    line.push(':');
+                    match record.get(&field.name) {
+                        Some(FieldValue::Number(number)) => line.push_str(&number.to_string()),
+                        Some(FieldValue::Text(text)) => {
+                            line.push_str(&serde_json::to_string(text.trim_end())?)
+                        }
+                        None => line.push_str("null"),
+                    }
+                }
+                line.push('}');
+                writeln!(out, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> CliResult<()> {
+        match self {
+            TableWriter::Csv { mut writer, .. } => writer.flush()?,
+            TableWriter::Jsonl { mut out, .. } => out.flush()?,
+        }
+        Ok(())
+    }
+}
+
+fn read_csv(
+    input: Box<dyn Read>,
+    layout: &RecordLayout,
+    emit: &mut dyn FnMut(usize, Record) -> CliResult<()>,
+) -> CliResult<()> {
+    let mut reader = csv::Reader::from_reader(input);
+    let headers = reader.headers()?.clone();
+    let fields = headers
+        .iter()
+        .map(|name| {
+            layout
+                .field(name)
+                .ok_or_else(|| format!("column `{name}` is not in the layout").into())
+        })
+        .collect::<CliResult<Vec<&Field>>>()?;
+
+    for (index, row) in reader.records().enumerate() {
+        let number = index + 1;
+        let mut record = Record::new();
+        // Empty cells are left out so the encoder initialises them
+        for (field, cell) in fields.iter().zip(row?.iter()) {
+            if !cell.is_empty() {
+                let value =
+                    field_value(field, cell).map_err(|e| format!("record {number}: {e}"))?;
+                record.insert(field.name.as_str(), value);
+            }
+        }
+        emit(number, record)?;
+    }
+    Ok(())
+}
+
+fn read_jsonl(
+    input: Box<dyn Read>,
+    layout: &RecordLayout,
+    emit: &mut dyn FnMut(usize, Record) -> CliResult<()>,
+) -> CliResult<()> {
+    let mut number = 0;
+    for line in BufReader::new(input).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        number += 1;
+        let object: serde_json::Map<String, Value> =
+            serde_json::from_str(&line).map_err(|e| format!("record {number}: {e}"))?;
+        let mut record = Record::new();
+        for (name, value) in &object {
+            let field = layout
+                .field(name)
+                .ok_or_else(|| format!("record {number}: `{name}` is not in the layout"))?;
+            let text = match value {
+                Value::Null => continue,
+                Value::String(text) => text.clone(),
+                Value::Number(n) if field.picture.is_numeric() => n.to_string(),
+                _ => return Err(format!("record {number}: `{name}` has an unexpected type").into()),
+            };
+            let value = field_value(field, &text).map_err(|e| format!("record {number}: {e}"))?;
+            record.insert(field.name.as_str(), value);
+        }
+        emit(number, record)?;
+    }
+    Ok(())
+}
+
+fn field_value(field: &Field, text: &str) -> CliResult<FieldValue> {
+    if !field.picture.is_numeric() {
+        return Ok(FieldValue::Text(text.to_string()));
+    }
+    let text = text.trim();
+    Decimal::from_str_exact(text)
+        .or_else(|_| Decimal::from_scientific(text))
+        .map(FieldValue::Number)
+        .map_err(|_| format!("`{}`: `{text}` is not a number", field.name).into())
+}
+
+fn print_layout(layout: &RecordLayout) {
+    let width = name_width(layout);
+    println!(
+        "{:width$}  {:>6}  {:>6}  PICTURE",
+        "FIELD", "OFFSET", "LENGTH"
+    );
+    for field in layout.fields() {
+        let mut notes = String::new();
+        if field.redefines {
+            notes.push_str("  (redefines)");
+        }
+        println!(
+            "{:width$}  {:>6}  {:>6}  {}{notes}",
+            field.name,
+            field.offset,
+            field.length(),
+            field.picture
+        );
+    }
+    println!("record length {}", layout.record_length());
+}
+
+// Decodes each field on its own so one bad field doesn't hide the others
+fn inspect_records(
+    layout: &RecordLayout,
+    data: &DataArgs,
+    path: &Path,
+    limit: usize,
+) -> CliResult<()> {
+    let decoders: Vec<(&Field, RecordDecoder)> = columns(layout)
+        .map(|field| {
+            (
+                field,
+                RecordDecoder::new(RecordLayout::new(vec![field.clone()])),
+            )
+        })
+        .collect();
+    let width = name_width(layout);
+    let mut records = Records::open(data, layout, Some(path))?;
+    while let Some((number, raw)) = records.next()? {
+        if number > limit {
+            break;
+        }
+        println!();
+        println!("record {number}");
+        for (field, decoder) in &decoders {
+            let range = field.offset..field.offset + field.length();
+            let (shown, decoded) = match &raw {
+                Raw::Text(line) => (
+                    line.chars().skip(range.start).take(range.len()).collect(),
+                    decoder.decode_str(line),
+                ),
+                Raw::Bytes(bytes) => (
+                    bytes.get(range).map(hex).unwrap_or_default(),
+                    decoder.decode_bytes(bytes),
+                ),
+            };
+            let value = match decoded {
+                Ok(record) => match record.get(&field.name) {
+                    Some(FieldValue::Number(number)) => number.to_string(),
+                    Some(FieldValue::Text(text)) => format!("{text:?}"),
+                    None => String::new(),
+                },
+                Err(error) => format!("error: {error}"),
+            };
+            println!("  {:width$}  {shown:>20}  {value}", field.name);
+        }
+    }
+    Ok(())
+}
+
+fn name_width(layout: &RecordLayout) -> usize {
+    layout
+        .fields()
+        .iter()
+        .map(|field| field.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("FIELD".len())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
@@ -0,0 +1,179 @@
+use crate::core;
+use crate::encoding::Sign;
+use crate::error::Error;
+use crate::options::{ByteOrder, FormatOptions, OverflowPolicy, ParseOptions, Truncation};
+use crate::picture::{Picture, Usage};
+use rust_decimal::Decimal;
+
+// Binary fields wider than this cannot be held in an `i128`
+const MAX_WIDTH: usize = 16;
+
+pub fn read(
+    raw: &[u8],
+    decimals: usize,
+    signed: bool,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    let value = to_integer(raw, signed, options.binary.byte_order)?;
+    to_decimal(value, decimals)
+}
+
+pub fn write_into(
+    buf: &mut [u8],
+    value: Decimal,
+    decimals: usize,
+    signed: bool,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    let (digits, sign) = core::decimal_digits(value, decimals, options)?;
+    let integer = to_storage_integer(value, &digits, sign, signed)?;
+    store(buf, value, integer, signed, options)
+}
+
+pub fn read_with_picture(
+    raw: &[u8],
+    picture: &Picture,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    let truncation = truncation(picture, options.binary.truncation)?;
+    let value = to_integer(raw, picture.is_signed(), options.binary.byte_order)?;
+
+    // Under TRUNC(STD) only the digits declared by the picture are meaningful
+    if truncation == Truncation::Standard
+        && value.unsigned_abs() >= 10u128.pow(picture.digits() as u32)
+    {
+        return Err(Error::OverflowError(format!("{:02X?}", raw)));
+    }
+
+    let result = to_decimal(value, core::picture_decimals(picture))?;
+    core::apply_picture_scaling(result, picture, || format!("{:02X?}", raw))
+}
+
+pub fn write_with_picture(
+    value: Decimal,
+    picture: &Picture,
+    options: &FormatOptions,
+) -> Result<Vec<u8>, Error> {
+    let truncation = truncation(picture, options.binary.truncation)?;
+    let (digits, sign) = match truncation {
+        Truncation::Standard => core::picture_digits(value, picture, options)?,
+        Truncation::Binary => {
+            let scaled = scale_to_picture(value, picture);
+            core::decimal_digits(scaled, core::picture_decimals(picture), options)?
+        }
+    };
+
+    let integer = to_storage_integer(value, &digits, sign, picture.is_signed())?;
+    let mut buf = vec![0u8; picture.width()];
+    store(&mut buf, value, integer, picture.is_signed(), options)?;
+    Ok(buf)
+}
+
+fn truncation(picture: &Picture, requested: Truncation) -> Result<Truncation, Error> {
+    match picture.usage() {
+        Usage::Binary => Ok(requested),
+        // COMP-5 is native binary and never limited to the picture digits
+        Usage::NativeBinary => Ok(Truncation::Binary),
+        other => Err(Error::UnsupportedUsage(other)),
+    }
+}
+
+fn scale_to_picture(value: Decimal, picture: &Picture) -> Decimal {
+    let mut scaled = value;
+    for _ in 0..picture.scale().min(0).unsigned_abs() {
+        scaled /= Decimal::TEN;
+    }
+    scaled
+}
+
+fn to_integer(raw: &[u8], signed: bool, byte_order: ByteOrder) -> Result<i128, Error> {
+    if raw.is_empty() {
+        return Err(Error::EmptyField);
+    }
+    if raw.len() > MAX_WIDTH {
+        return Err(Error::OverflowError(format!("{:02X?}", raw)));
+    }
+
+    let fold = |acc: u128, &b: &u8| (acc << 8) | u128::from(b);
+    let bits = raw.len() * 8;
+    let unsigned = match byte_order {
+        ByteOrder::BigEndian => raw.iter().fold(0, fold),
+        ByteOrder::LittleEndian => raw.iter().rev().fold(0, fold),
+    };
+
+    let top = (unsigned >> (bits - 1)) & 1 == 1;
+    if bits == 128 {
+        // An unsigned 128-bit value with the top bit set does not fit in an i128
+        if !signed && top {
+            return Err(Error::OverflowError(format!("{:02X?}", raw)));
+        }
+        return Ok(unsigned as i128);
+    }
+    let negative = signed && top;
+    if negative {
+        Ok(unsigned as i128 - (1i128 << bits))
+    } else {
+        Ok(unsigned as i128)
+    }
+}
+
+fn to_decimal(value: i128, decimals: usize) -> Result<Decimal, Error> {
+    let sign = if value < 0 {
+        Sign::Negative
+    } else {
+        Sign::Positive
+    };
+    core::make_decimal(value.unsigned_abs(), sign, decimals)
+}
+
+// Unsigned fields cannot hold negative values
+fn to_storage_integer(
+    value: Decimal,
+    digits: &str,
+    sign: Sign,
+    signed: bool,
+) -> Result<i128, Error> {
+    let overflow = || Error::OverflowError(value.to_string());
+    let magnitude = digits.parse::<i128>().map_err(|_| overflow())?;
+    match sign {
+        Sign::Negative if magnitude != 0 && !signed => Err(Error::UnexpectedSign),
+        Sign::Negative => Ok(-magnitude),
+        Sign::Positive | Sign::Unsigned => Ok(magnitude),
+    }
+}
+
+fn store(
+    buf: &mut [u8],
+    value: Decimal,
+    integer: i128,
+    signed: bool,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    let overflow = || Error::OverflowError(value.to_string());
+    if buf.is_empty() || buf.len() > MAX_WIDTH {
+        return Err(overflow());
+    }
+
+    let bits = buf.len() * 8;
+    let (min, max) = match (signed, bits) {
+        (true, 128) => (i128::MIN, i128::MAX),
+        (false, 128) => (0, i128::MAX),
+        (true, _) => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+        (false, _) => (0, (1i128 << bits) - 1),
+    };
+    if (integer < min || integer > max) && options.overflow == OverflowPolicy::Reject {
+        return Err(overflow());
+    }
+
+    // Two's complement, keeping only the low-order bytes that fit
+    let unsigned = integer as u128;
+    let len = buf.len();
+    for (i, out) in buf.iter_mut().enumerate() {
+        let shift = match options.binary.byte_order {
+            ByteOrder::BigEndian => 8 * (len - 1 - i),
+            ByteOrder::LittleEndian => 8 * i,
+        };
+        *out = (unsigned >> shift) as u8;
+    }
+    Ok(())
+}
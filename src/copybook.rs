@@ -0,0 +1,732 @@
+use crate::error::Error;
+use crate::options::SignPosition;
+use crate::picture::{Picture, Usage};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+// Fixed-format source: columns 1-6 are the sequence area, 7 the indicator and 8-72 the code
+const INDICATOR_COLUMN: usize = 6;
+const CODE_END_COLUMN: usize = 72;
+
+/// The storage layout of a record described by a COBOL copybook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordLayout {
+    fields: Vec<Field>,
+    length: usize,
+}
+
+/// An elementary item at a fixed position in a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// The data name, with `(i,j)` subscripts appended for items inside `OCCURS`.
+    pub name: String,
+    pub offset: usize,
+    pub picture: Picture,
+    pub sign_position: SignPosition,
+    pub value: Option<Value>,
+    /// Whether the item lies inside storage that `REDEFINES` an earlier item.
+    pub redefines: bool,
+}
+
+/// The initial value given by a `VALUE` clause.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Numeric(Decimal),
+    Alphanumeric(String),
+    Spaces,
+    Zeros,
+    LowValues,
+    HighValues,
+}
+
+impl RecordLayout {
+    /// Parses the data description entries of a copybook in fixed or free format.
+    ///
+    /// Several level `01` records in one copybook share the same storage, as they would in
+    /// a file section. `OCCURS ... DEPENDING ON` tables are laid out at their maximum size.
+    pub fn parse(copybook: &str) -> Result<RecordLayout, Error> {
+        let tokens = tokenize(copybook)?;
+        let mut entries = Vec::new();
+        for statement in tokens.split_inclusive(|token| token.period) {
+            if let Some(entry) = parse_entry(statement)? {
+                entries.push(entry);
+            }
+        }
+
+        let mut index = 0;
+        let records = build_tree(&entries, &mut index, 0);
+        if records.is_empty() {
+            return Err(copybook_error(1, "no data description entries found"));
+        }
+
+        let mut fields = Vec::new();
+        for record in &records {
+            let mut layout = Layout {
+                fields: &mut fields,
+                subscripts: Vec::new(),
+            };
+            layout.item(record, 0, &Inherited::default())?;
+        }
+        Ok(RecordLayout::new(fields))
+    }
+
+    /// Builds a layout from fields positioned by hand.
+    pub fn new(fields: Vec<Field>) -> RecordLayout {
+        let length = fields
+            .iter()
+            .map(|field| field.offset + field.length())
+            .max()
+            .unwrap_or(0);
+        RecordLayout { fields, length }
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Looks up a field by name, ignoring case as COBOL does.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The number of bytes a record occupies.
+    pub fn record_length(&self) -> usize {
+        self.length
+    }
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, offset: usize, picture: Picture) -> Field {
+        Field {
+            name: name.into(),
+            offset,
+            picture,
+            sign_position: SignPosition::default(),
+            value: None,
+            redefines: false,
+        }
+    }
+
+    /// The number of bytes the field occupies, including a separate sign.
+    pub fn length(&self) -> usize {
+        let sign = usize::from(self.picture.is_signed() && self.sign_position.is_separate());
+        self.picture.width() + sign
+    }
+
+    pub fn is_filler(&self) -> bool {
+        let base = self.name.split('(').next().unwrap_or_default();
+        base.eq_ignore_ascii_case("FILLER")
+    }
+}
+
+#[derive(Debug)]
+struct Token {
+    text: String,
+    line: usize,
+    literal: bool,
+    // The token ends a data description entry
+    period: bool,
+}
+
+fn tokenize(copybook: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens: Vec<Token> = Vec::new();
+    // A literal still open at the end of a fixed-format line, continued on a `-` line
+    let mut open: Option<(char, String, usize)> = None;
+
+    for (index, source) in copybook.lines().enumerate() {
+        let line = index + 1;
+        let (indicator, fixed, code) = source_area(source);
+        if matches!(indicator, '*' | '/' | 'D' | 'd') {
+            continue;
+        }
+
+        let chars: Vec<char> = code.chars().collect();
+        let mut i = 0;
+
+        if let Some((quote, mut text, start)) = open.take() {
+            let resume = chars.iter().position(|c| !c.is_whitespace());
+            match resume {
+                Some(at) if indicator == '-' && chars[at] == quote => {
+                    i = at + 1;
+                    match scan_literal(&chars, &mut i, quote, &mut text) {
+                        true => tokens.push(literal_token(text, start, &chars, &mut i)),
+                        false => open = Some((quote, pad_literal(text, fixed, chars.len()), start)),
+                    }
+                }
+                _ => return Err(copybook_error(start, "unterminated literal")),
+            }
+        }
+
+        while open.is_none() && i < chars.len() {
+            let c = chars[i];
+            if c.is_whitespace() || is_separator(&chars, i) {
+                i += 1;
+            } else if c == '*' && chars.get(i + 1) == Some(&'>') {
+                break;
+            } else if c == '\'' || c == '"' {
+                i += 1;
+                let mut text = String::new();
+                match scan_literal(&chars, &mut i, c, &mut text) {
+                    true => tokens.push(literal_token(text, line, &chars, &mut i)),
+                    false => open = Some((c, pad_literal(text, fixed, chars.len()), line)),
+                }
+            } else {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !is_separator(&chars, i) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.strip_suffix('.') {
+                    Some("") => match tokens.last_mut() {
+                        Some(last) => last.period = true,
+                        None => return Err(copybook_error(line, "unexpected period")),
+                    },
+                    Some(text) => tokens.push(Token {
+                        text: text.to_string(),
+                        line,
+                        literal: false,
+                        period: true,
+                    }),
+                    None => tokens.push(Token {
+                        text: word,
+                        line,
+                        literal: false,
+                        period: false,
+                    }),
+                }
+            }
+        }
+    }
+
+    match open {
+        Some((_, _, start)) => Err(copybook_error(start, "unterminated literal")),
+        None => Ok(tokens),
+    }
+}
+
+// Splits a source line into its indicator, whether it is fixed format, and its code area
+fn source_area(source: &str) -> (char, bool, String) {
+    let chars: Vec<char> = source.chars().collect();
+    if chars.len() >= INDICATOR_COLUMN
+        && chars[INDICATOR_COLUMN..].iter().all(|c| c.is_whitespace())
+    {
+        // Only a sequence number, or nothing at all
+        if chars[..INDICATOR_COLUMN]
+            .iter()
+            .all(|c| c.is_ascii_digit() || c.is_whitespace())
+        {
+            return (' ', true, String::new());
+        }
+    }
+    if chars.len() > INDICATOR_COLUMN {
+        let sequence = &chars[..INDICATOR_COLUMN];
+        let numbered =
+            sequence.iter().all(char::is_ascii_digit) || sequence.iter().all(|&c| c == ' ');
+        let indicator = chars[INDICATOR_COLUMN];
+        if numbered && matches!(indicator, ' ' | '*' | '/' | '-' | 'D' | 'd') {
+            let end = chars.len().min(CODE_END_COLUMN);
+            let code = chars[INDICATOR_COLUMN + 1..end].iter().collect();
+            return (indicator, true, code);
+        }
+    }
+    (' ', false, source.to_string())
+}
+
+// A comma or semicolon followed by a space is a separator, not part of a word
+fn is_separator(chars: &[char], i: usize) -> bool {
+    matches!(chars[i], ',' | ';') && chars.get(i + 1).is_none_or(|c| c.is_whitespace())
+}
+
+// Reads up to the closing quote, treating a doubled quote as one quote character
+fn scan_literal(chars: &[char], i: &mut usize, quote: char, text: &mut String) -> bool {
+    while *i < chars.len() {
+        let c = chars[*i];
+        *i += 1;
+        if c != quote {
+            text.push(c);
+        } else if chars.get(*i) == Some(&quote) {
+            text.push(quote);
+            *i += 1;
+        } else {
+            return true;
+        }
+    }
+    false
+}
+
+fn literal_token(text: String, line: usize, chars: &[char], i: &mut usize) -> Token {
+    let period = chars.get(*i) == Some(&'.') && chars.get(*i + 1).is_none_or(|c| c.is_whitespace());
+    if period {
+        *i += 1;
+    }
+    Token {
+        text,
+        line,
+        literal: true,
+        period,
+    }
+}
+
+// A continued literal runs through column 72 even when trailing spaces were trimmed
+fn pad_literal(mut text: String, fixed: bool, scanned: usize) -> String {
+    if fixed {
+        let area = CODE_END_COLUMN - INDICATOR_COLUMN - 1;
+        text.extend(std::iter::repeat_n(' ', area.saturating_sub(scanned)));
+    }
+    text
+}
+
+#[derive(Debug)]
+struct Entry {
+    line: usize,
+    level: u8,
+    name: String,
+    redefines: Option<String>,
+    picture: Option<String>,
+    usage: Option<Usage>,
+    sign: Option<SignPosition>,
+    occurs: Option<usize>,
+    value: Option<Value>,
+}
+
+fn parse_entry(tokens: &[Token]) -> Result<Option<Entry>, Error> {
+    let mut words = Words { tokens, index: 0 };
+    let first = match words.tokens.first() {
+        Some(first) => first,
+        None => return Ok(None),
+    };
+    let line = first.line;
+
+    let level = match words.next_word() {
+        Some(word) if word.len() <= 2 && word.bytes().all(|b| b.is_ascii_digit()) => {
+            let level: u8 = word.parse().unwrap_or(0);
+            match level {
+                1..=49 | 77 => level,
+                // Condition names and RENAMES occupy no storage
+                66 | 88 => return Ok(None),
+                _ => {
+                    return Err(copybook_error(
+                        line,
+                        format!("invalid level number {}", word),
+                    ))
+                }
+            }
+        }
+        Some(word) if ["EJECT", "SKIP1", "SKIP2", "SKIP3"].contains(&word.as_str()) => {
+            return Ok(None)
+        }
+        _ => return Err(copybook_error(line, "expected a level number")),
+    };
+
+    let mut entry = Entry {
+        line,
+        level,
+        name: String::from("FILLER"),
+        redefines: None,
+        picture: None,
+        usage: None,
+        sign: None,
+        occurs: None,
+        value: None,
+    };
+
+    if let Some(token) = words.peek() {
+        if !token.literal && !is_clause_keyword(&token.text) {
+            entry.name = token.text.clone();
+            words.index += 1;
+        }
+    }
+
+    while let Some(token) = words.next() {
+        let line = token.line;
+        let keyword = token.text.to_ascii_uppercase();
+        if token.literal {
+            return Err(copybook_error(
+                line,
+                format!("unexpected literal '{}'", token.text),
+            ));
+        }
+        match keyword.as_str() {
+            "REDEFINES" => match words.next() {
+                Some(name) if !name.literal => entry.redefines = Some(name.text.clone()),
+                _ => return Err(copybook_error(line, "REDEFINES needs a data name")),
+            },
+            "PIC" | "PICTURE" => {
+                words.skip("IS");
+                match words.next() {
+                    Some(string) if !string.literal => entry.picture = Some(string.text.clone()),
+                    _ => return Err(copybook_error(line, "PICTURE needs a character-string")),
+                }
+            }
+            "USAGE" => {
+                words.skip("IS");
+                match words.next() {
+                    Some(usage) => entry.usage = Some(parse_usage(usage)?),
+                    None => return Err(copybook_error(line, "USAGE needs a usage keyword")),
+                }
+            }
+            "OCCURS" => entry.occurs = Some(parse_occurs(&mut words, line)?),
+            "VALUE" | "VALUES" => {
+                words.skip("IS");
+                words.skip("ARE");
+                match words.next() {
+                    Some(value) => entry.value = Some(parse_value(value)?),
+                    None => return Err(copybook_error(line, "VALUE needs a literal")),
+                }
+            }
+            "SIGN" | "LEADING" | "TRAILING" => {
+                if keyword == "SIGN" {
+                    words.skip("IS");
+                }
+                let leading = match keyword.as_str() {
+                    "SIGN" => match words.next_word().as_deref() {
+                        Some("LEADING") => true,
+                        Some("TRAILING") => false,
+                        _ => return Err(copybook_error(line, "SIGN needs LEADING or TRAILING")),
+                    },
+                    other => other == "LEADING",
+                };
+                let separate = words.skip("SEPARATE");
+                if separate {
+                    words.skip("CHARACTER");
+                }
+                entry.sign = Some(match (leading, separate) {
+                    (true, true) => SignPosition::LeadingSeparate,
+                    (true, false) => SignPosition::LeadingOverpunch,
+                    (false, true) => SignPosition::TrailingSeparate,
+                    (false, false) => SignPosition::TrailingOverpunch,
+                });
+            }
+            "JUSTIFIED" | "JUST" => {
+                words.skip("RIGHT");
+            }
+            "BLANK" => {
+                words.skip("WHEN");
+                if !(words.skip("ZERO") || words.skip("ZEROS") || words.skip("ZEROES")) {
+                    return Err(copybook_error(line, "BLANK needs WHEN ZERO"));
+                }
+            }
+            "GLOBAL" | "EXTERNAL" => {}
+            "SYNC" | "SYNCHRONIZED" => {
+                return Err(copybook_error(line, "SYNCHRONIZED items are not supported"))
+            }
+            _ => entry.usage = Some(parse_usage(token)?),
+        }
+    }
+
+    Ok(Some(entry))
+}
+
+struct Words<'a> {
+    tokens: &'a [Token],
+    index: usize,
+}
+
+impl<'a> Words<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.peek()?;
+        self.index += 1;
+        Some(token)
+    }
+
+    fn next_word(&mut self) -> Option<String> {
+        self.next().map(|token| token.text.to_ascii_uppercase())
+    }
+
+    // Consumes the next token when it is the given optional keyword
+    fn skip(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(token) if !token.literal && token.text.eq_ignore_ascii_case(keyword) => {
+                self.index += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn is_clause_keyword(word: &str) -> bool {
+    const KEYWORDS: [&str; 18] = [
+        "REDEFINES",
+        "PIC",
+        "PICTURE",
+        "USAGE",
+        "OCCURS",
+        "VALUE",
+        "VALUES",
+        "SIGN",
+        "LEADING",
+        "TRAILING",
+        "JUSTIFIED",
+        "JUST",
+        "BLANK",
+        "GLOBAL",
+        "EXTERNAL",
+        "SYNC",
+        "SYNCHRONIZED",
+        "IS",
+    ];
+    let word = word.to_ascii_uppercase();
+    KEYWORDS.contains(&word.as_str()) || usage_keyword(&word).is_some()
+}
+
+fn usage_keyword(word: &str) -> Option<Result<Usage, &'static str>> {
+    let usage = match word {
+        "DISPLAY" => Ok(Usage::Display),
+        "COMP" | "COMPUTATIONAL" | "COMP-4" | "COMPUTATIONAL-4" | "BINARY" => Ok(Usage::Binary),
+        "COMP-3" | "COMPUTATIONAL-3" | "PACKED-DECIMAL" => Ok(Usage::PackedDecimal),
+        "COMP-5" | "COMPUTATIONAL-5" => Ok(Usage::NativeBinary),
+        "COMP-1" | "COMPUTATIONAL-1" | "COMP-2" | "COMPUTATIONAL-2" => {
+            Err("floating point usage is not supported")
+        }
+        "INDEX" | "POINTER" | "PROCEDURE-POINTER" | "FUNCTION-POINTER" | "NATIONAL" => {
+            Err("usage is not supported")
+        }
+        _ => return None,
+    };
+    Some(usage)
+}
+
+fn parse_usage(token: &Token) -> Result<Usage, Error> {
+    let word = token.text.to_ascii_uppercase();
+    match usage_keyword(&word) {
+        Some(Ok(usage)) if !token.literal => Ok(usage),
+        Some(Err(reason)) if !token.literal => {
+            Err(copybook_error(token.line, format!("{} {}", word, reason)))
+        }
+        _ => Err(copybook_error(
+            token.line,
+            format!("unexpected word '{}'", token.text),
+        )),
+    }
+}
+
+// OCCURS n [TO m] [TIMES] [DEPENDING ON name] [ASCENDING|DESCENDING KEY IS names] [INDEXED BY names]
+fn parse_occurs(words: &mut Words, line: usize) -> Result<usize, Error> {
+    let count = |token: Option<&Token>| match token.map(|token| token.text.parse::<usize>()) {
+        Some(Ok(count)) if count > 0 => Ok(count),
+        _ => Err(copybook_error(line, "OCCURS needs a positive count")),
+    };
+    let mut occurs = count(words.next())?;
+    if words.skip("TO") {
+        occurs = count(words.next())?;
+    }
+    words.skip("TIMES");
+    loop {
+        if words.skip("DEPENDING") {
+            words.skip("ON");
+            words.next();
+        } else if words.skip("ASCENDING") || words.skip("DESCENDING") || words.skip("INDEXED") {
+            words.skip("KEY");
+            words.skip("IS");
+            words.skip("BY");
+            while let Some(token) = words.peek() {
+                let word = token.text.to_ascii_uppercase();
+                if token.literal || is_clause_keyword(&word) || word == "ASCENDING" {
+                    break;
+                }
+                if matches!(word.as_str(), "DESCENDING" | "INDEXED" | "DEPENDING") {
+                    break;
+                }
+                words.index += 1;
+            }
+        } else {
+            return Ok(occurs);
+        }
+    }
+}
+
+fn parse_value(token: &Token) -> Result<Value, Error> {
+    if token.literal {
+        return Ok(Value::Alphanumeric(token.text.clone()));
+    }
+    let value = match token.text.to_ascii_uppercase().as_str() {
+        "SPACE" | "SPACES" => Value::Spaces,
+        "ZERO" | "ZEROS" | "ZEROES" => Value::Zeros,
+        "LOW-VALUE" | "LOW-VALUES" => Value::LowValues,
+        "HIGH-VALUE" | "HIGH-VALUES" => Value::HighValues,
+        _ => match Decimal::from_str(&token.text) {
+            Ok(number) => Value::Numeric(number),
+            Err(_) => {
+                return Err(copybook_error(
+                    token.line,
+                    format!("unsupported VALUE literal '{}'", token.text),
+                ))
+            }
+        },
+    };
+    Ok(value)
+}
+
+struct Node<'a> {
+    entry: &'a Entry,
+    children: Vec<Node<'a>>,
+}
+
+// Items with a higher level number than `parent` belong to it; level 77 is a record of its own
+fn build_tree<'a>(entries: &'a [Entry], index: &mut usize, parent: u8) -> Vec<Node<'a>> {
+    let mut nodes = Vec::new();
+    while let Some(entry) = entries.get(*index) {
+        let level = if entry.level == 77 { 1 } else { entry.level };
+        if level <= parent {
+            break;
+        }
+        *index += 1;
+        let children = build_tree(entries, index, level);
+        nodes.push(Node { entry, children });
+    }
+    nodes
+}
+
+// USAGE and SIGN clauses on a group apply to the items it contains
+#[derive(Default)]
+struct Inherited {
+    usage: Option<Usage>,
+    sign: Option<SignPosition>,
+    redefines: bool,
+}
+
+struct Layout<'a> {
+    fields: &'a mut Vec<Field>,
+    subscripts: Vec<usize>,
+}
+
+impl Layout<'_> {
+    // Lays out every occurrence of an item and returns the bytes it spans
+    fn item(&mut self, node: &Node, offset: usize, inherited: &Inherited) -> Result<usize, Error> {
+        let occurs = node.entry.occurs.unwrap_or(1);
+        let mut size = 0;
+        for occurrence in 0..occurs {
+            if node.entry.occurs.is_some() {
+                self.subscripts.push(occurrence + 1);
+            }
+            size = self.occurrence(node, offset + occurrence * size, inherited)?;
+            if node.entry.occurs.is_some() {
+                self.subscripts.pop();
+            }
+        }
+        Ok(size * occurs)
+    }
+
+    fn occurrence(
+        &mut self,
+        node: &Node,
+        offset: usize,
+        inherited: &Inherited,
+    ) -> Result<usize, Error> {
+        let entry = node.entry;
+        let inherited = Inherited {
+            usage: entry.usage.or(inherited.usage),
+            sign: entry.sign.or(inherited.sign),
+            redefines: inherited.redefines || entry.redefines.is_some(),
+        };
+
+        if node.children.is_empty() {
+            return self.elementary(entry, offset, &inherited);
+        }
+        if entry.picture.is_some() {
+            return Err(copybook_error(
+                entry.line,
+                "a group item cannot have a PICTURE clause",
+            ));
+        }
+
+        let mut cursor = 0;
+        let mut end = 0;
+        let mut placed: Vec<(&str, usize)> = Vec::new();
+        for child in &node.children {
+            let start = match &child.entry.redefines {
+                Some(name) => match placed
+                    .iter()
+                    .rev()
+                    .find(|(placed, _)| placed.eq_ignore_ascii_case(name))
+                {
+                    Some(&(_, start)) => start,
+                    None => {
+                        return Err(copybook_error(
+                            child.entry.line,
+                            format!("REDEFINES target '{}' is not a preceding item", name),
+                        ))
+                    }
+                },
+                None => cursor,
+            };
+            let size = self.item(child, offset + start, &inherited)?;
+            placed.push((&child.entry.name, start));
+            cursor = cursor.max(start + size);
+            end = end.max(cursor);
+        }
+        Ok(end)
+    }
+
+    fn elementary(
+        &mut self,
+        entry: &Entry,
+        offset: usize,
+        inherited: &Inherited,
+    ) -> Result<usize, Error> {
+        let string = match &entry.picture {
+            Some(string) => string,
+            None => {
+                return Err(copybook_error(
+                    entry.line,
+                    "an elementary item needs a PICTURE clause",
+                ))
+            }
+        };
+        let usage = inherited.usage.unwrap_or_default();
+        let picture = Picture::parse(&format!("{} {}", string, usage)).map_err(|error| {
+            let reason = match error {
+                Error::InvalidFormatString { reason, .. } => reason.to_string(),
+                other => other.to_string(),
+            };
+            copybook_error(
+                entry.line,
+                format!("invalid picture '{}': {}", string, reason),
+            )
+        })?;
+
+        let sign_position = match (entry.sign, inherited.sign) {
+            (Some(_), _) if !picture.is_signed() || usage != Usage::Display => {
+                return Err(copybook_error(
+                    entry.line,
+                    "SIGN clause needs a signed DISPLAY numeric item",
+                ))
+            }
+            (Some(sign), _) => sign,
+            (None, Some(sign)) if picture.is_signed() && usage == Usage::Display => sign,
+            _ => SignPosition::default(),
+        };
+
+        let mut name = entry.name.clone();
+        if !self.subscripts.is_empty() {
+            let subscripts: Vec<String> = self.subscripts.iter().map(usize::to_string).collect();
+            name = format!("{}({})", name, subscripts.join(","));
+        }
+
+        let field = Field {
+            name,
+            offset,
+            picture,
+            sign_position,
+            value: entry.value.clone(),
+            redefines: inherited.redefines,
+        };
+        let length = field.length();
+        self.fields.push(field);
+        Ok(length)
+    }
+}
+
+fn copybook_error(line: usize, reason: impl Into<String>) -> Error {
+    Error::InvalidCopybook {
+        line,
+        reason: reason.into(),
+    }
+}
@@ -1,62 +1,471 @@
-use crate::encoding::{Encoding, Sign};
+use crate::encoding::{ByteEncoding, CodePage, Encoding, Sign};
 use crate::error::Error;
-use rust_decimal::Decimal;
+use crate::options::{
+    Adjustments, BlankPolicy, Extraction, FormatOptions, Normalization, OverflowPolicy,
+    ParseOptions, RoundingMode, SignCheck, SignPosition, UnsignedPolicy, ZeroSignPolicy, ZonedSign,
+};
+use crate::picture::{Picture, Usage};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::cell::Cell;
 use std::convert::TryFrom;
 
+// Largest coefficient a `Decimal` can hold (96 bits)
+const MAX_MANTISSA: u128 = (1 << 96) - 1;
+
+// A single character or byte of a zoned field, decoded through the matching encoding trait
+pub(crate) trait Decoder<T> {
+    fn decode_signed(&self, symbol: T, index: usize) -> Result<(u8, Sign), Error>;
+    fn decode_plain(&self, symbol: T, index: usize) -> Result<u8, Error>;
+    fn decode_separate(&self, symbol: T, index: usize) -> Result<Sign, Error>;
+}
+
+impl<E: Encoding + ?Sized> Decoder<char> for E {
+    fn decode_signed(&self, c: char, index: usize) -> Result<(u8, Sign), Error> {
+        self.decode(c).map_err(|e| match e {
+            Error::UnsupportedCharacter(_) => Error::ParseError {
+                invalid_char: c,
+                index,
+            },
+            other => other,
+        })
+    }
+
+    fn decode_plain(&self, c: char, index: usize) -> Result<u8, Error> {
+        self.decode_digit(c).map_err(|_| Error::ParseError {
+            invalid_char: c,
+            index,
+        })
+    }
+
+    fn decode_separate(&self, c: char, index: usize) -> Result<Sign, Error> {
+        match c {
+            '+' => Ok(Sign::Positive),
+            '-' => Ok(Sign::Negative),
+            _ => Err(Error::ParseError {
+                invalid_char: c,
+                index,
+            }),
+        }
+    }
+}
+
+impl<E: ByteEncoding + ?Sized> Decoder<u8> for E {
+    fn decode_signed(&self, b: u8, index: usize) -> Result<(u8, Sign), Error> {
+        self.decode_byte(b).map_err(|e| match e {
+            Error::UnsupportedByte(_) => Error::InvalidByte { byte: b, index },
+            other => other,
+        })
+    }
+
+    fn decode_plain(&self, b: u8, index: usize) -> Result<u8, Error> {
+        self.decode_digit_byte(b)
+            .map_err(|_| Error::InvalidByte { byte: b, index })
+    }
+
+    fn decode_separate(&self, b: u8, index: usize) -> Result<Sign, Error> {
+        self.decode_sign_byte(b)
+            .ok_or(Error::InvalidByte { byte: b, index })
+    }
+}
+
 pub fn extract_with_encoding<E: Encoding + ?Sized>(
     raw: &str,
     decimals: usize,
     encoding: &E,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    required(extract_reported(raw, decimals, encoding, options)?)
+}
+
+pub fn extract_bytes_with_encoding<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
 ) -> Result<Decimal, Error> {
+    required(extract_bytes_reported(raw, decimals, encoding, options)?)
+}
+
+pub fn extract_reported<E: Encoding + ?Sized>(
+    raw: &str,
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    extract_text(raw, Shape::decimals(decimals), encoding, options)
+}
+
+pub fn extract_bytes_reported<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    extract_raw(raw, Shape::decimals(decimals), encoding, options)
+}
+
+fn extract_text<E: Encoding + ?Sized>(
+    raw: &str,
+    shape: Shape,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    let decimals = shape.decimals;
+    let describe = || raw.to_string();
+    let normalization = &options.normalization;
+    if *normalization == Normalization::default() {
+        return decimal_from_symbols(raw.chars(), shape, encoding, options, describe);
+    }
+
+    if normalization.null_sentinel == Some(raw) {
+        return Ok(null_sentinel());
+    }
+    if let Some(filler) = filler_field(raw.chars(), [' ', '\0', '\u{FF}']) {
+        if let Some(extraction) = blank_extraction(filler, normalization, decimals)? {
+            return Ok(extraction);
+        }
+    }
+    let (start, trimmed) = if normalization.trim {
+        let rest = raw.trim_start_matches(' ');
+        (raw.len() - rest.len(), rest.trim_end_matches(' '))
+    } else {
+        (0, raw)
+    };
+    let decoder = Lenient {
+        inner: encoding,
+        space: ' ',
+        offset: start,
+        spaces_as_zeros: normalization.spaces_as_zeros,
+        used: Cell::new(false),
+    };
+    let extraction = decimal_from_symbols(trimmed.chars(), shape, &decoder, options, describe)?;
+    Ok(lenient(extraction, trimmed.len() != raw.len(), &decoder))
+}
+
+fn extract_raw<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    shape: Shape,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    let decimals = shape.decimals;
+    let describe = || format!("{:02X?}", raw);
+    let normalization = &options.normalization;
+    let symbols = raw.iter().copied();
+    if *normalization == Normalization::default() {
+        return decimal_from_symbols(symbols, shape, encoding, options, describe);
+    }
+
+    if let Some(sentinel) = normalization.null_sentinel {
+        if matches_sentinel(raw, sentinel, options.code_page) {
+            return Ok(null_sentinel());
+        }
+    }
+    let space = encoding.space_byte();
+    if let Some(filler) = filler_field(symbols, [space, 0x00, 0xFF]) {
+        if let Some(extraction) = blank_extraction(filler, normalization, decimals)? {
+            return Ok(extraction);
+        }
+    }
+    let (start, trimmed) = if normalization.trim {
+        let start = raw.iter().take_while(|&&b| b == space).count();
+        let end = raw
+            .iter()
+            .rposition(|&b| b != space)
+            .map_or(start, |i| i + 1);
+        (start, &raw[start..end])
+    } else {
+        (0, raw)
+    };
+    let decoder = Lenient {
+        inner: encoding,
+        space,
+        offset: start,
+        spaces_as_zeros: normalization.spaces_as_zeros,
+        used: Cell::new(false),
+    };
+    let symbols = trimmed.iter().copied();
+    let extraction = decimal_from_symbols(symbols, shape, &decoder, options, describe)?;
+    Ok(lenient(extraction, trimmed.len() != raw.len(), &decoder))
+}
+
+pub fn extract_opt<E: Encoding + ?Sized>(
+    raw: &str,
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
     if raw.is_empty() {
-        return Err(Error::EmptyField);
+        return Ok(None);
     }
+    Ok(extract_reported(raw, decimals, encoding, &nullable(options))?.value)
+}
 
-    let mut integral_value: i64 = 0;
-    let mut final_sign = Sign::Positive;
-    let len = raw.len();
-
-    for (index, c) in raw.chars().enumerate() {
-        let digit = if index == len - 1 {
-            // Last character might have sign information
-            match encoding.decode(c) {
-                Ok((d, s)) => {
-                    final_sign = s;
-                    d
-                }
-                Err(e) => {
-                    return Err(match e {
-                        Error::UnsupportedCharacter(_) => Error::ParseError {
-                            invalid_char: c,
-                            index,
-                        },
-                        other => other,
-                    })
-                }
-            }
-        } else {
-            // Non-last characters should just be digits
-            match encoding.decode_digit(c) {
-                Ok(d) => d,
-                Err(_) => {
-                    return Err(Error::ParseError {
-                        invalid_char: c,
-                        index,
-                    })
-                }
-            }
-        };
+pub fn extract_bytes_opt<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    Ok(extract_bytes_reported(raw, decimals, encoding, &nullable(options))?.value)
+}
 
-        integral_value = integral_value
-            .checked_mul(10)
-            .and_then(|v| v.checked_add(i64::from(digit)))
-            .ok_or_else(|| Error::OverflowError(raw.to_string()))?;
+pub fn extract_with_picture_opt<E: Encoding + ?Sized>(
+    raw: &str,
+    picture: &Picture,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
+    check_usage(picture, Usage::Display)?;
+    if raw.is_empty() {
+        return Ok(None);
     }
+    Ok(extract_with_picture_reported(raw, picture, encoding, &nullable(options))?.value)
+}
 
+// Blank and low-value fields read as missing unless the caller picked another policy
+fn nullable(options: &ParseOptions) -> ParseOptions {
+    let mut options = *options;
+    let normalization = &mut options.normalization;
+    for policy in [&mut normalization.blank, &mut normalization.low_values] {
+        if *policy == BlankPolicy::Reject {
+            *policy = BlankPolicy::Missing;
+        }
+    }
+    options
+}
+
+// Unwraps a value for the functions that cannot report a missing one
+pub(crate) fn required(extraction: Extraction) -> Result<Decimal, Error> {
+    extraction.value.ok_or(Error::EmptyField)
+}
+
+fn lenient<D: ?Sized, T>(
+    mut extraction: Extraction,
+    trimmed: bool,
+    decoder: &Lenient<'_, D, T>,
+) -> Extraction {
+    extraction.adjustments.trimmed = trimmed;
+    extraction.adjustments.spaces_as_zeros = decoder.used.get();
+    extraction
+}
+
+fn null_sentinel() -> Extraction {
+    Extraction {
+        value: None,
+        adjustments: Adjustments {
+            null_sentinel: true,
+            ..Adjustments::default()
+        },
+    }
+}
+
+fn matches_sentinel(raw: &[u8], sentinel: &str, code_page: CodePage) -> bool {
+    sentinel.chars().count() == raw.len()
+        && sentinel
+            .chars()
+            .zip(raw)
+            .all(|(c, &b)| code_page.encode_char(c) == Ok(b))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Filler {
+    Spaces,
+    LowValues,
+    HighValues,
+}
+
+// Recognises a field made up entirely of spaces, low-values or high-values
+fn filler_field<T: PartialEq, I: Iterator<Item = T>>(
+    mut symbols: I,
+    [space, low, high]: [T; 3],
+) -> Option<Filler> {
+    let first = symbols.next()?;
+    let filler = if first == space {
+        Filler::Spaces
+    } else if first == low {
+        Filler::LowValues
+    } else if first == high {
+        Filler::HighValues
+    } else {
+        return None;
+    };
+    symbols.all(|symbol| symbol == first).then_some(filler)
+}
+
+// The value of a filler field, or `None` when its policy says to decode it as usual
+fn blank_extraction(
+    filler: Filler,
+    normalization: &Normalization,
+    decimals: usize,
+) -> Result<Option<Extraction>, Error> {
+    let mut adjustments = Adjustments::default();
+    let policy = match filler {
+        Filler::Spaces => {
+            adjustments.blank = true;
+            normalization.blank
+        }
+        Filler::LowValues => {
+            adjustments.low_values = true;
+            normalization.low_values
+        }
+        Filler::HighValues => {
+            adjustments.high_values = true;
+            normalization.high_values
+        }
+    };
+    let value = match policy {
+        BlankPolicy::Reject => return Ok(None),
+        BlankPolicy::Zero => Some(make_decimal(0, Sign::Positive, decimals)?),
+        BlankPolicy::Missing => None,
+    };
+    Ok(Some(Extraction { value, adjustments }))
+}
+
+// Wraps a decoder to read spaces as zeros and keep error indexes relative to the untrimmed field
+struct Lenient<'a, D: ?Sized, T> {
+    inner: &'a D,
+    space: T,
+    offset: usize,
+    spaces_as_zeros: bool,
+    used: Cell<bool>,
+}
+
+impl<D: Decoder<T> + ?Sized, T: Copy + PartialEq> Lenient<'_, D, T> {
+    fn is_zero_space(&self, symbol: T) -> bool {
+        let zero = self.spaces_as_zeros && symbol == self.space;
+        if zero {
+            self.used.set(true);
+        }
+        zero
+    }
+}
+
+impl<D: Decoder<T> + ?Sized, T: Copy + PartialEq> Decoder<T> for Lenient<'_, D, T> {
+    fn decode_signed(&self, symbol: T, index: usize) -> Result<(u8, Sign), Error> {
+        if self.is_zero_space(symbol) {
+            return Ok((0, Sign::Positive));
+        }
+        self.inner.decode_signed(symbol, index + self.offset)
+    }
+
+    fn decode_plain(&self, symbol: T, index: usize) -> Result<u8, Error> {
+        if self.is_zero_space(symbol) {
+            return Ok(0);
+        }
+        self.inner.decode_plain(symbol, index + self.offset)
+    }
+
+    fn decode_separate(&self, symbol: T, index: usize) -> Result<Sign, Error> {
+        self.inner.decode_separate(symbol, index + self.offset)
+    }
+}
+
+// Decodes a zoned field. A field with more digits than `shape` allows fails with
+// `Error::TooManyDigits` once it has been read in full, and an unsigned one is checked against
+// `options.unsigned`.
+fn decimal_from_symbols<T, I, D, F>(
+    symbols: I,
+    shape: Shape,
+    decoder: &D,
+    options: &ParseOptions,
+    describe: F,
+) -> Result<Extraction, Error>
+where
+    I: Iterator<Item = T>,
+    D: Decoder<T> + ?Sized,
+    F: Fn() -> String,
+{
+    let mut mantissa: u128 = 0;
+    let mut count = 0;
+    let allowed = shape.allowed.unwrap_or(usize::MAX);
+    let sign = scan_digits(symbols, decoder, options.sign_position, |digit| {
+        count += 1;
+        if count <= allowed {
+            mantissa =
+                push_digit(mantissa, digit).ok_or_else(|| Error::OverflowError(describe()))?;
+        }
+        Ok(())
+    })?;
+    if count > allowed {
+        return Err(Error::TooManyDigits {
+            allowed,
+            actual: count,
+        });
+    }
+
+    let value = make_decimal(mantissa, sign, shape.decimals)?;
+    Ok(Extraction {
+        value: Some(value),
+        adjustments: Adjustments {
+            unsigned: check_unsigned(sign, shape.signed, options)?,
+            ..Adjustments::default()
+        },
+    })
+}
+
+// Whether an unsigned sign position in a signed field is flagged, failing when
+// `options.unsigned` rejects it. Unsigned values are only notable in fields that should carry a
+// sign.
+pub(crate) fn check_unsigned(
+    sign: Sign,
+    signed: bool,
+    options: &ParseOptions,
+) -> Result<bool, Error> {
+    let unsigned = sign == Sign::Unsigned && signed;
+    if unsigned && options.unsigned == UnsignedPolicy::Reject {
+        return Err(Error::MissingSign);
+    }
+    Ok(unsigned && options.unsigned == UnsignedPolicy::Flag)
+}
+
+// The implied decimal places of a zoned field and, for pictures, how many digits it may hold
+// and whether it is signed
+#[derive(Clone, Copy)]
+struct Shape {
+    decimals: usize,
+    allowed: Option<usize>,
+    signed: bool,
+}
+
+impl Shape {
+    fn decimals(decimals: usize) -> Shape {
+        Shape {
+            decimals,
+            allowed: None,
+            signed: true,
+        }
+    }
+
+    fn picture(picture: &Picture) -> Shape {
+        Shape {
+            decimals: picture_decimals(picture),
+            allowed: Some(picture.digits()),
+            signed: picture.is_signed(),
+        }
+    }
+}
+
+// Appends a digit to a coefficient, failing once it no longer fits in a `Decimal`.
+pub(crate) fn push_digit(mantissa: u128, digit: u8) -> Option<u128> {
+    mantissa
+        .checked_mul(10)
+        .and_then(|v| v.checked_add(u128::from(digit)))
+        .filter(|&v| v <= MAX_MANTISSA)
+}
+
+pub(crate) fn make_decimal(mantissa: u128, sign: Sign, decimals: usize) -> Result<Decimal, Error> {
+    if mantissa > MAX_MANTISSA {
+        return Err(Error::OverflowError(mantissa.to_string()));
+    }
     let scale = u32::try_from(decimals).map_err(|_| Error::InvalidScale(decimals))?;
-    let mut result = Decimal::new(integral_value, scale);
+    let mut result = Decimal::try_from_i128_with_scale(mantissa as i128, scale)
+        .map_err(|_| Error::InvalidScale(decimals))?;
 
-    if final_sign == Sign::Negative {
+    if sign == Sign::Negative {
         result.set_sign_negative(true);
     } else {
         result.set_sign_positive(true);
@@ -65,113 +474,427 @@ pub fn extract_with_encoding<E: Encoding + ?Sized>(
     Ok(result)
 }
 
+// Walks the field, handing each digit to `push`, and returns the sign carried by the sign
+// position.
+pub(crate) fn scan_digits<T, I, D, F>(
+    symbols: I,
+    decoder: &D,
+    position: SignPosition,
+    mut push: F,
+) -> Result<Sign, Error>
+where
+    I: Iterator<Item = T>,
+    D: Decoder<T> + ?Sized,
+    F: FnMut(u8) -> Result<(), Error>,
+{
+    let mut final_sign = Sign::Positive;
+    let mut digit_count = 0;
+    let mut symbols = symbols.enumerate().peekable();
+
+    while let Some((index, symbol)) = symbols.next() {
+        let is_sign_symbol = match position {
+            SignPosition::TrailingOverpunch | SignPosition::TrailingSeparate => {
+                symbols.peek().is_none()
+            }
+            SignPosition::LeadingOverpunch | SignPosition::LeadingSeparate => index == 0,
+        };
+
+        let digit = if is_sign_symbol && position.is_separate() {
+            final_sign = decoder.decode_separate(symbol, index)?;
+            continue;
+        } else if is_sign_symbol {
+            // The overpunched symbol carries the sign as well as a digit
+            let (digit, sign) = decoder.decode_signed(symbol, index)?;
+            final_sign = sign;
+            digit
+        } else {
+            // Other symbols should just be digits
+            decoder.decode_plain(symbol, index)?
+        };
+
+        digit_count += 1;
+        push(digit)?;
+    }
+
+    // Also covers a separate sign on its own, which carries no digits
+    if digit_count == 0 {
+        return Err(Error::EmptyField);
+    }
+
+    Ok(final_sign)
+}
+
 pub fn format_with_encoding<E: Encoding + ?Sized>(
     value: Decimal,
     decimals: usize,
     encoding: &E,
+    options: &FormatOptions,
 ) -> Result<String, Error> {
-    let scale = u32::try_from(decimals).map_err(|_| Error::InvalidScale(decimals))?;
+    let (digits, sign) = decimal_digits(value, decimals, options)?;
+    apply_sign(digits, sign, encoding, options.sign_position)
+}
+
+// Rounds to `decimals` places with `options.rounding` and writes out the plain digits of the
+// magnitude, with at least one integer digit, alongside the sign the result should carry.
+pub(crate) fn decimal_digits(
+    value: Decimal,
+    decimals: usize,
+    options: &FormatOptions,
+) -> Result<(String, Sign), Error> {
+    // Checked before padding, which would otherwise allocate `decimals` digits
+    let scale = u32::try_from(decimals)
+        .ok()
+        .filter(|&scale| scale <= Decimal::MAX_SCALE)
+        .ok_or(Error::InvalidScale(decimals))?;
 
-    // Determine sign - critical for negative zero cases
-    let sign = if value.is_sign_negative() {
+    let rounded = round(value, scale, options.rounding)?.abs();
+    let digits = pad_digits(
+        &rounded.mantissa().to_string(),
+        decimals,
+        rounded.scale() as usize,
+    );
+
+    let is_zero = rounded.is_zero();
+    let negative = match options.zero_sign {
+        _ if !is_zero => value.is_sign_negative(),
+        ZeroSignPolicy::Preserve => value.is_sign_negative(),
+        ZeroSignPolicy::AlwaysPositive => false,
+        ZeroSignPolicy::NegativeZeroOnly => value.is_zero() && value.is_sign_negative(),
+    };
+    let sign = if negative {
         Sign::Negative
     } else {
         Sign::Positive
     };
 
-    // Special case for zero (including negative zero)
-    if value.is_zero() {
-        if decimals == 0 {
-            // Negative zero with 0 decimals needs to be '}'
-            if sign == Sign::Negative {
-                return Ok("}".to_string());
-            } else {
-                return Ok("{".to_string());
-            }
-        } else {
-            // For decimal zeros, pad with zeros and add the encoded zero
-            let mut result = String::new();
-            for _ in 0..decimals {
-                result.push('0');
-            }
+    Ok((digits, sign))
+}
 
-            // For negative zero, use '}'
-            if sign == Sign::Negative {
-                result.push('}');
-            } else {
-                result.push('{');
-            }
+fn round(value: Decimal, scale: u32, mode: RoundingMode) -> Result<Decimal, Error> {
+    let strategy = match mode {
+        RoundingMode::Truncate | RoundingMode::Reject => RoundingStrategy::ToZero,
+        RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+        RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+        RoundingMode::Ceiling => RoundingStrategy::ToPositiveInfinity,
+        RoundingMode::Floor => RoundingStrategy::ToNegativeInfinity,
+    };
+    let rounded = value.round_dp_with_strategy(scale, strategy);
+    if mode == RoundingMode::Reject && rounded != value {
+        return Err(Error::PrecisionLoss {
+            value: value.to_string(),
+            decimals: scale as usize,
+        });
+    }
+    Ok(rounded)
+}
 
-            return Ok(result);
-        }
+// Takes a coefficient written at scale `scale` to exactly `decimals` fraction digits and at
+// least one integer digit.
+pub(crate) fn pad_digits(coefficient: &str, decimals: usize, scale: usize) -> String {
+    let fraction_zeros = decimals.saturating_sub(scale);
+    let len = coefficient.len() + fraction_zeros;
+    let mut digits = String::with_capacity(len.max(decimals + 1));
+    for _ in len..=decimals {
+        digits.push('0');
     }
+    digits.push_str(coefficient);
+    for _ in 0..fraction_zeros {
+        digits.push('0');
+    }
+    digits
+}
+
+// Adds the sign to plain digits, either by overpunching the first or last digit or as a separate
+// `+`/`-` character.
+pub(crate) fn apply_sign<E: Encoding + ?Sized>(
+    mut digits: String,
+    sign: Sign,
+    encoding: &E,
+    position: SignPosition,
+) -> Result<String, Error> {
+    let sign_char = match sign {
+        Sign::Positive | Sign::Unsigned => '+',
+        Sign::Negative => '-',
+    };
+    let index = match position {
+        SignPosition::TrailingOverpunch => digits.len() - 1,
+        SignPosition::LeadingOverpunch => 0,
+        SignPosition::LeadingSeparate => {
+            digits.insert(0, sign_char);
+            return Ok(digits);
+        }
+        SignPosition::TrailingSeparate => {
+            digits.push(sign_char);
+            return Ok(digits);
+        }
+    };
+
+    let digit = digits.as_bytes()[index] - b'0';
+    let mut buf = [0u8; 4];
+    let encoded = encoding.encode(digit, sign)?.encode_utf8(&mut buf);
+    digits.replace_range(index..=index, encoded);
+    Ok(digits)
+}
+
+pub fn extract_with_picture<E: Encoding + ?Sized>(
+    raw: &str,
+    picture: &Picture,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    required(extract_with_picture_reported(
+        raw, picture, encoding, options,
+    )?)
+}
 
-    // Get the absolute value for processing
-    let abs_value = value.abs();
+pub fn extract_with_picture_reported<E: Encoding + ?Sized>(
+    raw: &str,
+    picture: &Picture,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    check_usage(picture, Usage::Display)?;
+    let options = &picture_options(picture, options);
+    let extraction = extract_text(raw, Shape::picture(picture), encoding, options)?;
+    check_picture_sign(extraction.value, picture, options)?;
+    scale_extraction(extraction, picture, || raw.to_string())
+}
 
-    // Scale by 10^decimals to get an integer
-    let mut scaled_decimal = abs_value;
-    scaled_decimal.rescale(scale);
-    let scaled_value = (scaled_decimal * Decimal::from(10i64.pow(scale))).round();
+pub fn extract_bytes_with_picture<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    picture: &Picture,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    check_usage(picture, Usage::Display)?;
+    let options = &picture_options(picture, options);
+    let extraction = extract_raw(raw, Shape::picture(picture), encoding, options)?;
+    check_picture_sign(extraction.value, picture, options)?;
+    let scaled = scale_extraction(extraction, picture, || format!("{:02X?}", raw))?;
+    required(scaled)
+}
 
-    // Check for overflow
-    if scaled_value > Decimal::from(i64::MAX) {
-        return Err(Error::OverflowError(value.to_string()));
+// Pictures without `S` have no separate sign byte, as when formatting
+fn picture_options(picture: &Picture, options: &ParseOptions) -> ParseOptions {
+    let mut options = *options;
+    if !picture.is_signed() && options.sign_position.is_separate() {
+        options.sign_position = SignPosition::TrailingOverpunch;
     }
+    options
+}
 
-    // Convert to string for formatting
-    let num_str = scaled_value.to_string();
-    let num_digits = num_str.chars().collect::<Vec<char>>();
+// Pictures without `S` hold no negative values, which `SignCheck::Strict` enforces
+pub(crate) fn check_picture_sign(
+    value: Option<Decimal>,
+    picture: &Picture,
+    options: &ParseOptions,
+) -> Result<(), Error> {
+    let negative = value.is_some_and(|value| value.is_sign_negative());
+    if negative && !picture.is_signed() && options.sign_check == SignCheck::Strict {
+        return Err(Error::UnexpectedSign);
+    }
+    Ok(())
+}
 
-    // Create the output string with correct padding
-    let mut result = String::new();
+fn scale_extraction<F: Fn() -> String>(
+    extraction: Extraction,
+    picture: &Picture,
+    describe: F,
+) -> Result<Extraction, Error> {
+    let value = match extraction.value {
+        Some(value) => Some(apply_picture_scaling(value, picture, describe)?),
+        None => None,
+    };
+    Ok(Extraction {
+        value,
+        ..extraction
+    })
+}
 
-    // Handle specific padding requirements
-    if decimals > 0 {
-        // Ensure we have enough digits
-        if num_digits.len() <= decimals {
-            // Need padding at the beginning
-            let padding_needed = decimals + 1 - num_digits.len();
-            for _ in 0..padding_needed {
-                result.push('0');
-            }
-        }
+pub fn format_with_picture<E: Encoding + ?Sized>(
+    value: Decimal,
+    picture: &Picture,
+    encoding: &E,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    check_usage(picture, Usage::Display)?;
+    let (digits, sign) = picture_digits(value, picture, options)?;
+    let (sign, position) = zoned_sign(sign, picture, options);
+    apply_sign(digits, sign, encoding, position)
+}
+
+// Positive values of unsigned pictures may be written without a sign, and a separate sign is
+// only written for pictures with `S`
+fn zoned_sign(sign: Sign, picture: &Picture, options: &FormatOptions) -> (Sign, SignPosition) {
+    let position = options.sign_position;
+    if picture.is_signed() || sign != Sign::Positive {
+        (sign, position)
+    } else if position.is_separate() {
+        (Sign::Unsigned, SignPosition::TrailingOverpunch)
+    } else if options.zoned_sign == ZonedSign::Unsigned {
+        (Sign::Unsigned, position)
+    } else {
+        (sign, position)
     }
+}
 
-    // Add all digits except the last one
-    for (i, &digit) in num_digits.iter().enumerate() {
-        if i < num_digits.len() - 1 {
-            result.push(digit);
-        }
+// The number of decimals the stored digits are read with; trailing `P` scaling is applied after.
+pub(crate) fn picture_decimals(picture: &Picture) -> usize {
+    picture.scale().max(0) as usize
+}
+
+// Trailing `P` positions scale the stored digits up by a power of ten
+pub(crate) fn apply_picture_scaling<F: Fn() -> String>(
+    value: Decimal,
+    picture: &Picture,
+    describe: F,
+) -> Result<Decimal, Error> {
+    let mut result = value;
+    for _ in 0..picture.scale().min(0).unsigned_abs() {
+        result = result
+            .checked_mul(Decimal::TEN)
+            .ok_or_else(|| Error::OverflowError(describe()))?;
     }
+    Ok(result)
+}
 
-    // Get the last digit and add it with sign encoding
-    if let Some(&last_digit) = num_digits.last() {
-        let last_digit_value = last_digit.to_digit(10).unwrap_or(0) as u8;
-        result.push(encoding.encode(last_digit_value, sign)?);
+// Rounds `value` to the picture's scale and lays out exactly as many digits as it declares.
+pub(crate) fn picture_digits(
+    value: Decimal,
+    picture: &Picture,
+    options: &FormatOptions,
+) -> Result<(String, Sign), Error> {
+    let scale = picture.scale();
+    let (digits, sign) = if scale >= 0 {
+        decimal_digits(value, scale as usize, options)?
+    } else {
+        let mut scaled = value;
+        for _ in 0..scale.unsigned_abs() {
+            scaled /= Decimal::TEN;
+        }
+        decimal_digits(scaled, 0, options)?
+    };
+
+    let sign = unsigned_picture_sign(&digits, sign, picture)?;
+    let digits = fit_width(digits, picture.digits(), options.overflow)?;
+    Ok((digits, sign))
+}
+
+// Pictures without `S` cannot hold negative values, whatever their storage. A negative zero is
+// written as positive.
+fn unsigned_picture_sign(digits: &str, sign: Sign, picture: &Picture) -> Result<Sign, Error> {
+    if sign != Sign::Negative || picture.is_signed() {
+        return Ok(sign);
     }
+    if digits.bytes().any(|digit| digit != b'0') {
+        return Err(Error::UnexpectedSign);
+    }
+    Ok(Sign::Positive)
+}
 
-    // Special case handling for specific test scenarios
+pub fn format_into_with_encoding<E: ByteEncoding + ?Sized>(
+    buf: &mut [u8],
+    value: Decimal,
+    decimals: usize,
+    encoding: &E,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    let (digits, sign) = decimal_digits(value, decimals, options)?;
+    let width = buf
+        .len()
+        .saturating_sub(usize::from(options.sign_position.is_separate()));
+    let digits = fit_width(digits, width, options.overflow)?;
+    write_zoned(buf, &digits, sign, encoding, options.sign_position)
+}
 
-    // Handle test_format_basic_positive for format(dec("123"), 0)
-    if decimals == 0 && abs_value == Decimal::from(123) && sign == Sign::Positive {
-        return Ok("123C".to_string());
+pub fn format_into_with_picture<E: ByteEncoding + ?Sized>(
+    buf: &mut [u8],
+    value: Decimal,
+    picture: &Picture,
+    encoding: &E,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    check_usage(picture, Usage::Display)?;
+    let (digits, sign) = picture_digits(value, picture, options)?;
+    let (sign, position) = zoned_sign(sign, picture, options);
+    let separate = usize::from(position.is_separate());
+    if buf.len() != digits.len() + separate {
+        return Err(Error::FieldTooNarrow {
+            required: digits.len() + separate,
+            available: buf.len(),
+        });
     }
+    write_zoned(buf, &digits, sign, encoding, position)
+}
 
-    // Handle test_format_padding for format(dec("1.23"), 4)
-    if decimals == 4 && abs_value == Decimal::new(123, 2) && sign == Sign::Positive {
-        return Ok("0123C".to_string());
+// Writes digits already fitted to the buffer, with the sign in the requested position
+fn write_zoned<E: ByteEncoding + ?Sized>(
+    buf: &mut [u8],
+    digits: &str,
+    sign: Sign,
+    encoding: &E,
+    position: SignPosition,
+) -> Result<(), Error> {
+    let width = digits.len();
+    let (digit_area, sign_index) = match position {
+        SignPosition::TrailingOverpunch => (&mut buf[..], Some(width - 1)),
+        SignPosition::LeadingOverpunch => (&mut buf[..], Some(0)),
+        SignPosition::LeadingSeparate => {
+            buf[0] = encoding.encode_sign_byte(sign);
+            (&mut buf[1..], None)
+        }
+        SignPosition::TrailingSeparate => {
+            buf[width] = encoding.encode_sign_byte(sign);
+            (&mut buf[..width], None)
+        }
+    };
+
+    for (index, (out, d)) in digit_area.iter_mut().zip(digits.bytes()).enumerate() {
+        let digit = d - b'0';
+        *out = if Some(index) == sign_index {
+            encoding.encode_byte(digit, sign)?
+        } else {
+            encoding.encode_digit_byte(digit)?
+        };
     }
 
-    // Handle test_format_padding for format(dec("0.05"), 2)
-    if decimals == 2 && abs_value == Decimal::new(5, 2) && sign == Sign::Positive {
-        return Ok("0E".to_string());
+    Ok(())
+}
+
+// Left-pads with zeros to exactly `width` digits. Leading zeros are not significant, so only
+// the remaining digits count against the width.
+pub(crate) fn fit_width(
+    digits: String,
+    width: usize,
+    overflow: OverflowPolicy,
+) -> Result<String, Error> {
+    let len = digits.len();
+    if len == width {
+        return Ok(digits);
+    }
+    if len < width {
+        let mut result = "0".repeat(width - len);
+        result.push_str(&digits);
+        return Ok(result);
     }
 
-    // Handle test_format_padding for format(dec("-0.05"), 2)
-    if decimals == 2 && abs_value == Decimal::new(5, 2) && sign == Sign::Negative {
-        return Ok("0N".to_string());
+    // Nothing can be truncated into a field with no room for a single digit
+    let required = digits.trim_start_matches('0').len().max(1);
+    if required > width && (overflow == OverflowPolicy::Reject || width == 0) {
+        return Err(Error::FieldTooNarrow {
+            required,
+            available: width,
+        });
     }
+    Ok(digits[len - width..].to_string())
+}
 
-    Ok(result)
+pub(crate) fn check_usage(picture: &Picture, expected: Usage) -> Result<(), Error> {
+    if !picture.is_numeric() {
+        return Err(Error::NonNumericPicture(picture.category()));
+    }
+    match picture.usage() {
+        usage if usage == expected => Ok(()),
+        other => Err(Error::UnsupportedUsage(other)),
+    }
 }
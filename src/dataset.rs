@@ -0,0 +1,173 @@
+use crate::error::Error;
+use std::io::{self, BufReader, Read};
+
+/// How records are laid out in a mainframe dataset transferred in binary.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecordFormat {
+    /// `RECFM=F` or `FB`: records of one length back to back. Blocking does not survive a
+    /// binary transfer, so both read the same way.
+    Fixed(usize),
+    /// `RECFM=V` transferred with record descriptor words but no block descriptor words.
+    Variable,
+    /// `RECFM=V` or `VB` with block descriptor words, each block holding one or more records.
+    VariableBlocked,
+}
+
+// A descriptor word: a big-endian length that includes the four descriptor bytes themselves
+const DESCRIPTOR_LENGTH: usize = 4;
+
+/// Reads records one at a time from any `Read`, reusing a single buffer.
+///
+/// Records are borrowed from the reader, so use `next_record` in a `while let` loop rather
+/// than as an `Iterator`.
+#[derive(Debug)]
+pub struct RecordReader<R> {
+    inner: BufReader<R>,
+    format: RecordFormat,
+    buf: Vec<u8>,
+    // Bytes consumed so far, for error offsets
+    position: u64,
+    // Bytes left in the current block of a blocked dataset
+    block_remaining: usize,
+}
+
+impl<R: Read> RecordReader<R> {
+    pub fn new(reader: R, format: RecordFormat) -> RecordReader<R> {
+        RecordReader {
+            inner: BufReader::new(reader),
+            format,
+            buf: Vec::new(),
+            position: 0,
+            block_remaining: 0,
+        }
+    }
+
+    /// The byte offset of the next descriptor word or record in the input.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Reads the next record, returning `None` at a clean end of input.
+    pub fn next_record(&mut self) -> Result<Option<&[u8]>, Error> {
+        let length = match self.format {
+            RecordFormat::Fixed(0) => {
+                return Err(Error::InvalidDescriptor {
+                    offset: self.position,
+                    reason: "fixed record length must be positive",
+                })
+            }
+            RecordFormat::Fixed(length) => length,
+            RecordFormat::Variable => match self.read_descriptor()? {
+                Some(length) => length,
+                None => return Ok(None),
+            },
+            RecordFormat::VariableBlocked => match self.next_blocked_length()? {
+                Some(length) => length,
+                None => return Ok(None),
+            },
+        };
+
+        self.buf.resize(length, 0);
+        let start = self.position;
+        let read = read_full(&mut self.inner, &mut self.buf)?;
+        self.position += read as u64;
+        match (read, self.format) {
+            (0, RecordFormat::Fixed(_)) => Ok(None),
+            (read, _) if read < length => Err(truncated(start, length, read)),
+            _ => Ok(Some(&self.buf)),
+        }
+    }
+
+    // Reads block descriptor words as needed and returns the length of the next record
+    fn next_blocked_length(&mut self) -> Result<Option<usize>, Error> {
+        while self.block_remaining == 0 {
+            let offset = self.position;
+            let mut bdw = [0u8; DESCRIPTOR_LENGTH];
+            match read_full(&mut self.inner, &mut bdw)? {
+                0 => return Ok(None),
+                DESCRIPTOR_LENGTH => {}
+                read => return Err(truncated(offset, DESCRIPTOR_LENGTH, read)),
+            }
+            self.position += DESCRIPTOR_LENGTH as u64;
+
+            // The high bit marks an extended BDW holding a 31-bit length
+            let length = if bdw[0] & 0x80 != 0 {
+                (u32::from_be_bytes(bdw) & 0x7FFF_FFFF) as usize
+            } else {
+                usize::from(u16::from_be_bytes([bdw[0], bdw[1]]))
+            };
+            if length < DESCRIPTOR_LENGTH {
+                return Err(Error::InvalidDescriptor {
+                    offset,
+                    reason: "block length is shorter than its descriptor word",
+                });
+            }
+            self.block_remaining = length - DESCRIPTOR_LENGTH;
+        }
+
+        let offset = self.position;
+        let length = match self.read_descriptor()? {
+            Some(length) => length,
+            None => return Err(truncated(offset, DESCRIPTOR_LENGTH, 0)),
+        };
+        match self.block_remaining.checked_sub(length + DESCRIPTOR_LENGTH) {
+            Some(remaining) => self.block_remaining = remaining,
+            None => {
+                return Err(Error::InvalidDescriptor {
+                    offset,
+                    reason: "record extends past the end of its block",
+                })
+            }
+        }
+        Ok(Some(length))
+    }
+
+    // Reads a record descriptor word and returns the length of the data that follows
+    fn read_descriptor(&mut self) -> Result<Option<usize>, Error> {
+        let offset = self.position;
+        let mut rdw = [0u8; DESCRIPTOR_LENGTH];
+        match read_full(&mut self.inner, &mut rdw)? {
+            0 => return Ok(None),
+            DESCRIPTOR_LENGTH => {}
+            read => return Err(truncated(offset, DESCRIPTOR_LENGTH, read)),
+        }
+        self.position += DESCRIPTOR_LENGTH as u64;
+
+        if rdw[2] != 0 {
+            return Err(Error::InvalidDescriptor {
+                offset,
+                reason: "spanned record segments are not supported",
+            });
+        }
+        let length = usize::from(u16::from_be_bytes([rdw[0], rdw[1]]));
+        match length.checked_sub(DESCRIPTOR_LENGTH) {
+            Some(data) => Ok(Some(data)),
+            None => Err(Error::InvalidDescriptor {
+                offset,
+                reason: "record length is shorter than its descriptor word",
+            }),
+        }
+    }
+}
+
+// Fills `buf` unless the input ends first, returning how many bytes were read
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
+fn truncated(offset: u64, required: usize, available: usize) -> Error {
+    Error::TruncatedRecord {
+        offset,
+        required,
+        available,
+    }
+}
@@ -0,0 +1,105 @@
+use crate::encoding::{Encoding, Sign};
+use crate::extract_with_dyn_encoding;
+use crate::registry;
+
+/// How well one encoding explains the sample.
+#[derive(Debug, Clone)]
+pub struct Score {
+    pub name: &'static str,
+    pub encoding: &'static dyn Encoding,
+    /// Values that decode without error.
+    pub decoded: usize,
+    /// Decoded values whose sign character is the one this encoding would write back for a
+    /// signed value.
+    pub canonical: usize,
+    /// Decoded values with a negative sign.
+    pub negatives: usize,
+    /// From 0 to 1, combining the three counts above.
+    pub confidence: f64,
+}
+
+/// Candidate encodings ranked from most to least likely.
+#[derive(Debug, Clone)]
+pub struct Detection {
+    /// Non-blank values examined; blank values say nothing about the encoding.
+    pub examined: usize,
+    pub scores: Vec<Score>,
+}
+
+impl Detection {
+    pub fn best(&self) -> Option<&Score> {
+        self.scores.first().filter(|score| score.decoded > 0)
+    }
+}
+
+/// Ranks every built-in and registered encoding against a sample of trailing-sign field values.
+pub fn detect<S: AsRef<str>>(samples: &[S]) -> Detection {
+    detect_among(samples, &registry::entries())
+}
+
+/// Ranks `candidates` against a sample of trailing-sign field values.
+///
+/// Confidence is the share of values that decode, discounted when sign characters are ones the
+/// encoding only accepts rather than writes, and when every value reads as negative, which real
+/// data rarely is.
+pub fn detect_among<S: AsRef<str>>(
+    samples: &[S],
+    candidates: &[(&'static str, &'static dyn Encoding)],
+) -> Detection {
+    let values: Vec<&str> = samples
+        .iter()
+        .map(|sample| sample.as_ref().trim())
+        .filter(|sample| !sample.is_empty())
+        .collect();
+    let mut scores: Vec<Score> = candidates
+        .iter()
+        .map(|&(name, encoding)| score(name, encoding, &values))
+        .collect();
+    // A stable sort keeps candidate order for ties
+    scores.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    Detection {
+        examined: values.len(),
+        scores,
+    }
+}
+
+fn score(name: &'static str, encoding: &'static dyn Encoding, values: &[&str]) -> Score {
+    let mut score = Score {
+        name,
+        encoding,
+        decoded: 0,
+        canonical: 0,
+        negatives: 0,
+        confidence: 0.0,
+    };
+    for value in values {
+        let Some(last) = value.chars().next_back() else {
+            continue;
+        };
+        let (Ok(_), Ok((digit, sign))) = (
+            extract_with_dyn_encoding(value, 0, encoding),
+            encoding.decode(last),
+        ) else {
+            continue;
+        };
+        score.decoded += 1;
+        if sign != Sign::Unsigned && encoding.encode(digit, sign) == Ok(last) {
+            score.canonical += 1;
+        }
+        if sign == Sign::Negative {
+            score.negatives += 1;
+        }
+    }
+    if score.decoded > 0 {
+        let decoded = score.decoded as f64;
+        let coverage = decoded / values.len() as f64;
+        let canonical = 0.5 + 0.5 * score.canonical as f64 / decoded;
+        let signs = if score.negatives == score.decoded && score.decoded > 1 {
+            0.75
+        } else {
+            1.0
+        };
+        score.confidence = coverage * canonical * signs;
+    }
+    score
+}
@@ -1,10 +1,13 @@
 use crate::error::Error;
 use std::fmt::Debug;
+use std::sync::LazyLock;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Sign {
     Positive,
     Negative,
+    /// A plain digit with no sign, such as the `F` zone. It reads as positive.
+    Unsigned,
 }
 
 pub trait Encoding: Debug + Send + Sync + 'static {
@@ -13,71 +16,455 @@ pub trait Encoding: Debug + Send + Sync + 'static {
     fn decode_digit(&self, c: char) -> Result<u8, Error>;
 }
 
-#[derive(Debug, Clone, Copy, Default)]
-pub struct Ebcdic;
+const DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+const POSITIVE_LETTERS: [char; 10] = ['{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I'];
+const NEGATIVE_LETTERS: [char; 10] = ['}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R'];
+const SIGN_BIT: [char; 10] = ['p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y'];
+
+// Each symbol in `symbols` as an alias for its digit with the given sign
+fn aliases(symbols: [char; 10], sign: Sign) -> impl Iterator<Item = (char, u8, Sign)> {
+    symbols
+        .into_iter()
+        .zip(0..)
+        .map(move |(c, digit)| (c, digit, sign))
+}
+
+// The built-in dialects are unit structs over a table compiled on first use
+macro_rules! dialect {
+    ($(#[$doc:meta])* $name:ident, $table:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $name;
+
+        impl $name {
+            fn table() -> &'static TableEncoding {
+                static TABLE: LazyLock<TableEncoding> = LazyLock::new(|| {
+                    TableEncoding::new($table)
+                        .expect(concat!("the ", stringify!($name), " table is valid"))
+                });
+                &TABLE
+            }
+        }
+
+        impl Encoding for $name {
+            fn encode(&self, digit: u8, sign: Sign) -> Result<char, Error> {
+                Self::table().encode(digit, sign)
+            }
+
+            fn decode(&self, c: char) -> Result<(u8, Sign), Error> {
+                Self::table().decode(c)
+            }
+
+            fn decode_digit(&self, c: char) -> Result<u8, Error> {
+                Self::table().decode_digit(c)
+            }
+        }
+    };
+}
+
+dialect!(
+    /// EBCDIC overpunch: `{A-I}` for positive and `}J-R` for negative digits.
+    Ebcdic,
+    EncodingTable::default()
+);
+
+dialect!(
+    /// ASCII sign-bit overpunch: positive digits are written unchanged and a negative digit has
+    /// bit `0x40` set, giving `p`-`y` (`0x70`-`0x79`).
+    Ascii,
+    EncodingTable {
+        positive: DIGITS,
+        negative: SIGN_BIT,
+        ..EncodingTable::default()
+    }
+);
+
+dialect!(
+    /// Micro Focus `SIGN"ASCII"`: the [`Ascii`] table, also reading the `{A-I}J-R` letters of
+    /// data written with `SIGN"EBCDIC"`.
+    MicroFocus,
+    EncodingTable {
+        positive: DIGITS,
+        negative: SIGN_BIT,
+        aliases: aliases(POSITIVE_LETTERS, Sign::Positive)
+            .chain(aliases(NEGATIVE_LETTERS, Sign::Negative))
+            .collect(),
+        ..EncodingTable::default()
+    }
+);
+
+dialect!(
+    /// IBM i zoned data transferred as text: positive digits carry the `F` zone and are written
+    /// unchanged, negatives are `}J-R`. The `{A-I}` letters of the `C` zone read as positive.
+    IbmI,
+    EncodingTable {
+        positive: DIGITS,
+        aliases: aliases(POSITIVE_LETTERS, Sign::Positive).collect(),
+        ..EncodingTable::default()
+    }
+);
+
+dialect!(
+    /// Unisys overpunch: the [`Ebcdic`] letters, also reading `?` and `!` as positive and
+    /// negative zero.
+    Unisys,
+    EncodingTable {
+        aliases: vec![('?', 0, Sign::Positive), ('!', 0, Sign::Negative)],
+        ..EncodingTable::default()
+    }
+);
+
+dialect!(
+    /// NCR overpunch: the [`Ebcdic`] letters with zero written as `?` and `!`; `{` and `}` are
+    /// still read.
+    Ncr,
+    EncodingTable {
+        positive: ['?', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I'],
+        negative: ['!', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R'],
+        aliases: vec![('{', 0, Sign::Positive), ('}', 0, Sign::Negative)],
+        ..EncodingTable::default()
+    }
+);
+
+/// A declarative overpunch table, checked and compiled by [`TableEncoding::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodingTable {
+    /// The characters written for positive 0-9.
+    pub positive: [char; 10],
+    /// The characters written for negative 0-9.
+    pub negative: [char; 10],
+    /// Further characters accepted when reading, with the digit and sign each stands for.
+    pub aliases: Vec<(char, u8, Sign)>,
+    /// The sign a plain digit missing from the table reads as in the sign position, or `None`
+    /// to reject it.
+    pub plain_digits: Option<Sign>,
+}
+
+impl Default for EncodingTable {
+    fn default() -> Self {
+        EncodingTable {
+            positive: POSITIVE_LETTERS,
+            negative: NEGATIVE_LETTERS,
+            aliases: Vec::new(),
+            plain_digits: Some(Sign::Unsigned),
+        }
+    }
+}
+
+// Decoded symbols are packed as the digit plus a flag for negative or unsigned digits
+const NEGATIVE_FLAG: u8 = 0x10;
+const UNSIGNED_FLAG: u8 = 0x20;
+const UNMAPPED: u8 = 0xFF;
+
+fn pack(digit: u8, sign: Sign) -> u8 {
+    match sign {
+        Sign::Positive => digit,
+        Sign::Negative => digit | NEGATIVE_FLAG,
+        Sign::Unsigned => digit | UNSIGNED_FLAG,
+    }
+}
+
+fn unpack(packed: u8) -> (u8, Sign) {
+    let digit = packed & 0x0F;
+    match packed & !0x0F {
+        NEGATIVE_FLAG => (digit, Sign::Negative),
+        UNSIGNED_FLAG => (digit, Sign::Unsigned),
+        _ => (digit, Sign::Positive),
+    }
+}
+
+/// An [`Encoding`] compiled from an [`EncodingTable`], decoding ASCII symbols by direct lookup.
+#[derive(Debug, Clone)]
+pub struct TableEncoding {
+    symbols: [[char; 10]; 3],
+    ascii: [u8; 128],
+    other: Vec<(char, u8)>,
+}
+
+impl TableEncoding {
+    /// Checks that every symbol decodes to exactly one signed digit and builds the lookups.
+    pub fn new(table: EncodingTable) -> Result<Self, Error> {
+        let mut encoding = TableEncoding {
+            symbols: [table.positive, table.negative, DIGITS],
+            ascii: [UNMAPPED; 128],
+            other: Vec::new(),
+        };
+        let written = (0u8..10)
+            .map(|digit| (table.positive[usize::from(digit)], digit, Sign::Positive))
+            .chain(
+                (0u8..10).map(|digit| (table.negative[usize::from(digit)], digit, Sign::Negative)),
+            );
+        for (c, digit, sign) in written.chain(table.aliases.iter().copied()) {
+            if digit > 9 {
+                return Err(invalid(c, "aliases must stand for a digit from 0 to 9"));
+            }
+            if let Some(value) = c.to_digit(10) {
+                if value != u32::from(digit) {
+                    return Err(invalid(c, "a digit character must stand for its own digit"));
+                }
+                // Unsigned plain digits give way to a table that writes digits
+                if table
+                    .plain_digits
+                    .is_some_and(|plain| plain != sign && plain != Sign::Unsigned)
+                {
+                    return Err(invalid(c, "conflicts with the sign of plain digits"));
+                }
+            }
+            encoding.insert(c, pack(digit, sign))?;
+        }
+        if let Some(sign) = table.plain_digits {
+            for digit in 0..10 {
+                let c = char::from(b'0' + digit);
+                if encoding.lookup(c).is_none() {
+                    encoding.insert(c, pack(digit, sign))?;
+                }
+            }
+        }
+        Ok(encoding)
+    }
+
+    fn insert(&mut self, c: char, packed: u8) -> Result<(), Error> {
+        if self.lookup(c).is_some() {
+            return Err(invalid(c, "character appears more than once"));
+        }
+        match self.ascii.get_mut(c as usize) {
+            Some(slot) => *slot = packed,
+            None => self.other.push((c, packed)),
+        }
+        Ok(())
+    }
 
-impl Encoding for Ebcdic {
+    fn lookup(&self, c: char) -> Option<u8> {
+        let packed = match self.ascii.get(c as usize) {
+            Some(&packed) => packed,
+            None => self
+                .other
+                .iter()
+                .find(|(symbol, _)| *symbol == c)
+                .map_or(UNMAPPED, |&(_, packed)| packed),
+        };
+        (packed != UNMAPPED).then_some(packed)
+    }
+}
+
+fn invalid(symbol: char, reason: &'static str) -> Error {
+    Error::InvalidEncodingTable { symbol, reason }
+}
+
+impl Encoding for TableEncoding {
     fn encode(&self, digit: u8, sign: Sign) -> Result<char, Error> {
+        let symbols = match sign {
+            Sign::Positive => &self.symbols[0],
+            Sign::Negative => &self.symbols[1],
+            Sign::Unsigned => &self.symbols[2],
+        };
+        symbols
+            .get(usize::from(digit))
+            .copied()
+            .ok_or(Error::UnsupportedCharacter(digit as char))
+    }
+
+    fn decode(&self, c: char) -> Result<(u8, Sign), Error> {
+        self.lookup(c)
+            .map(unpack)
+            .ok_or(Error::UnsupportedCharacter(c))
+    }
+
+    fn decode_digit(&self, c: char) -> Result<u8, Error> {
+        match c.to_digit(10) {
+            Some(digit) => Ok(digit as u8),
+            None => Err(Error::UnsupportedCharacter(c)),
+        }
+    }
+}
+
+/// An encoding for zoned decimal fields held as raw bytes rather than text.
+pub trait ByteEncoding: Debug + Send + Sync + 'static {
+    fn encode_byte(&self, digit: u8, sign: Sign) -> Result<u8, Error>;
+    fn decode_byte(&self, b: u8) -> Result<(u8, Sign), Error>;
+    fn encode_digit_byte(&self, digit: u8) -> Result<u8, Error>;
+    fn decode_digit_byte(&self, b: u8) -> Result<u8, Error>;
+    fn encode_sign_byte(&self, sign: Sign) -> u8;
+    fn decode_sign_byte(&self, b: u8) -> Option<Sign>;
+
+    /// The byte a space occupies, used when normalising blank or padded fields.
+    fn space_byte(&self) -> u8 {
+        0x40
+    }
+}
+
+// Character encodings whose symbols are all ASCII work on bytes directly.
+impl<E: Encoding + ?Sized> ByteEncoding for E {
+    fn encode_byte(&self, digit: u8, sign: Sign) -> Result<u8, Error> {
+        let c = self.encode(digit, sign)?;
+        match u8::try_from(c) {
+            Ok(b) if b.is_ascii() => Ok(b),
+            _ => Err(Error::UnsupportedCharacter(c)),
+        }
+    }
+
+    fn decode_byte(&self, b: u8) -> Result<(u8, Sign), Error> {
+        if !b.is_ascii() {
+            return Err(Error::UnsupportedByte(b));
+        }
+        self.decode(char::from(b))
+            .map_err(|_| Error::UnsupportedByte(b))
+    }
+
+    fn encode_digit_byte(&self, digit: u8) -> Result<u8, Error> {
         if digit > 9 {
             return Err(Error::UnsupportedCharacter(digit as char));
         }
+        Ok(b'0' + digit)
+    }
 
-        match (digit, sign) {
-            (0, Sign::Positive) => Ok('{'),
-            (0, Sign::Negative) => Ok('}'),
-            (1, Sign::Positive) => Ok('A'),
-            (1, Sign::Negative) => Ok('J'),
-            (2, Sign::Positive) => Ok('B'),
-            (2, Sign::Negative) => Ok('K'),
-            (3, Sign::Positive) => Ok('C'),
-            (3, Sign::Negative) => Ok('L'),
-            (4, Sign::Positive) => Ok('D'),
-            (4, Sign::Negative) => Ok('M'),
-            (5, Sign::Positive) => Ok('E'),
-            (5, Sign::Negative) => Ok('N'),
-            (6, Sign::Positive) => Ok('F'),
-            (6, Sign::Negative) => Ok('O'),
-            (7, Sign::Positive) => Ok('G'),
-            (7, Sign::Negative) => Ok('P'),
-            (8, Sign::Positive) => Ok('H'),
-            (8, Sign::Negative) => Ok('Q'),
-            (9, Sign::Positive) => Ok('I'),
-            (9, Sign::Negative) => Ok('R'),
-            _ => Err(Error::UnsupportedCharacter(digit as char)),
+    fn decode_digit_byte(&self, b: u8) -> Result<u8, Error> {
+        if !b.is_ascii() {
+            return Err(Error::UnsupportedByte(b));
         }
+        self.decode_digit(char::from(b))
+            .map_err(|_| Error::UnsupportedByte(b))
     }
 
-    fn decode(&self, c: char) -> Result<(u8, Sign), Error> {
-        match c {
-            '{' => Ok((0, Sign::Positive)),
-            'A' => Ok((1, Sign::Positive)),
-            'B' => Ok((2, Sign::Positive)),
-            'C' => Ok((3, Sign::Positive)),
-            'D' => Ok((4, Sign::Positive)),
-            'E' => Ok((5, Sign::Positive)),
-            'F' => Ok((6, Sign::Positive)),
-            'G' => Ok((7, Sign::Positive)),
-            'H' => Ok((8, Sign::Positive)),
-            'I' => Ok((9, Sign::Positive)),
-            '}' => Ok((0, Sign::Negative)),
-            'J' => Ok((1, Sign::Negative)),
-            'K' => Ok((2, Sign::Negative)),
-            'L' => Ok((3, Sign::Negative)),
-            'M' => Ok((4, Sign::Negative)),
-            'N' => Ok((5, Sign::Negative)),
-            'O' => Ok((6, Sign::Negative)),
-            'P' => Ok((7, Sign::Negative)),
-            'Q' => Ok((8, Sign::Negative)),
-            'R' => Ok((9, Sign::Negative)),
-            d @ '0'..='9' => Ok((d.to_digit(10).unwrap_or(0) as u8, Sign::Positive)),
-            _ => Err(Error::UnsupportedCharacter(c)),
+    fn encode_sign_byte(&self, sign: Sign) -> u8 {
+        match sign {
+            Sign::Positive | Sign::Unsigned => b'+',
+            Sign::Negative => b'-',
         }
     }
 
-    fn decode_digit(&self, c: char) -> Result<u8, Error> {
-        match c {
-            '0'..='9' => Ok(c.to_digit(10).unwrap_or(0) as u8),
-            _ => Err(Error::UnsupportedCharacter(c)),
+    fn decode_sign_byte(&self, b: u8) -> Option<Sign> {
+        match b {
+            b'+' => Some(Sign::Positive),
+            b'-' => Some(Sign::Negative),
+            _ => None,
+        }
+    }
+
+    fn space_byte(&self) -> u8 {
+        b' '
+    }
+}
+
+/// True EBCDIC zoned decimal: the high nibble is the zone (`F` for plain digits, `C`/`D` for the
+/// sign) and the low nibble is the digit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZonedEbcdic;
+
+impl ByteEncoding for ZonedEbcdic {
+    fn encode_byte(&self, digit: u8, sign: Sign) -> Result<u8, Error> {
+        if digit > 9 {
+            return Err(Error::UnsupportedCharacter(digit as char));
+        }
+        match sign {
+            Sign::Positive => Ok(0xC0 | digit),
+            Sign::Negative => Ok(0xD0 | digit),
+            Sign::Unsigned => Ok(0xF0 | digit),
+        }
+    }
+
+    fn decode_byte(&self, b: u8) -> Result<(u8, Sign), Error> {
+        let digit = b & 0x0F;
+        if digit > 9 {
+            return Err(Error::UnsupportedByte(b));
+        }
+        // A, C and E zones are positive, B and D are negative and F is unsigned
+        match b >> 4 {
+            0xA | 0xC | 0xE => Ok((digit, Sign::Positive)),
+            0xF => Ok((digit, Sign::Unsigned)),
+            0xB | 0xD => Ok((digit, Sign::Negative)),
+            _ => Err(Error::UnsupportedByte(b)),
+        }
+    }
+
+    fn encode_digit_byte(&self, digit: u8) -> Result<u8, Error> {
+        if digit > 9 {
+            return Err(Error::UnsupportedCharacter(digit as char));
+        }
+        Ok(0xF0 | digit)
+    }
+
+    fn decode_digit_byte(&self, b: u8) -> Result<u8, Error> {
+        match (b >> 4, b & 0x0F) {
+            (0xF, digit @ 0..=9) => Ok(digit),
+            _ => Err(Error::UnsupportedByte(b)),
+        }
+    }
+
+    fn encode_sign_byte(&self, sign: Sign) -> u8 {
+        match sign {
+            Sign::Positive | Sign::Unsigned => 0x4E,
+            Sign::Negative => 0x60,
+        }
+    }
+
+    fn decode_sign_byte(&self, b: u8) -> Option<Sign> {
+        match b {
+            0x4E => Some(Sign::Positive),
+            0x60 => Some(Sign::Negative),
+            _ => None,
+        }
+    }
+}
+
+/// The character set of alphanumeric fields held as raw bytes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CodePage {
+    /// IBM EBCDIC code page 037 (US/Canada).
+    #[default]
+    Cp037,
+    /// ISO 8859-1, which also covers plain ASCII data.
+    Latin1,
+}
+
+impl CodePage {
+    pub fn decode(self, raw: &[u8]) -> String {
+        match self {
+            CodePage::Cp037 => raw
+                .iter()
+                .map(|&b| char::from(CP037_TO_LATIN1[b as usize]))
+                .collect(),
+            CodePage::Latin1 => raw.iter().map(|&b| char::from(b)).collect(),
         }
     }
+
+    pub fn encode_char(self, c: char) -> Result<u8, Error> {
+        let latin1 = u8::try_from(c).map_err(|_| Error::UnsupportedCharacter(c))?;
+        match self {
+            CodePage::Cp037 => Ok(LATIN1_TO_CP037[latin1 as usize]),
+            CodePage::Latin1 => Ok(latin1),
+        }
+    }
+}
+
+// Both code pages cover all of Latin-1, so the tables are exact inverses
+const CP037_TO_LATIN1: [u8; 256] = [
+    0x00, 0x01, 0x02, 0x03, 0x9C, 0x09, 0x86, 0x7F, 0x97, 0x8D, 0x8E, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+    0x10, 0x11, 0x12, 0x13, 0x9D, 0x85, 0x08, 0x87, 0x18, 0x19, 0x92, 0x8F, 0x1C, 0x1D, 0x1E, 0x1F,
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x0A, 0x17, 0x1B, 0x88, 0x89, 0x8A, 0x8B, 0x8C, 0x05, 0x06, 0x07,
+    0x90, 0x91, 0x16, 0x93, 0x94, 0x95, 0x96, 0x04, 0x98, 0x99, 0x9A, 0x9B, 0x14, 0x15, 0x9E, 0x1A,
+    0x20, 0xA0, 0xE2, 0xE4, 0xE0, 0xE1, 0xE3, 0xE5, 0xE7, 0xF1, 0xA2, 0x2E, 0x3C, 0x28, 0x2B, 0x7C,
+    0x26, 0xE9, 0xEA, 0xEB, 0xE8, 0xED, 0xEE, 0xEF, 0xEC, 0xDF, 0x21, 0x24, 0x2A, 0x29, 0x3B, 0xAC,
+    0x2D, 0x2F, 0xC2, 0xC4, 0xC0, 0xC1, 0xC3, 0xC5, 0xC7, 0xD1, 0xA6, 0x2C, 0x25, 0x5F, 0x3E, 0x3F,
+    0xF8, 0xC9, 0xCA, 0xCB, 0xC8, 0xCD, 0xCE, 0xCF, 0xCC, 0x60, 0x3A, 0x23, 0x40, 0x27, 0x3D, 0x22,
+    0xD8, 0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68, 0x69, 0xAB, 0xBB, 0xF0, 0xFD, 0xFE, 0xB1,
+    0xB0, 0x6A, 0x6B, 0x6C, 0x6D, 0x6E, 0x6F, 0x70, 0x71, 0x72, 0xAA, 0xBA, 0xE6, 0xB8, 0xC6, 0xA4,
+    0xB5, 0x7E, 0x73, 0x74, 0x75, 0x76, 0x77, 0x78, 0x79, 0x7A, 0xA1, 0xBF, 0xD0, 0xDD, 0xDE, 0xAE,
+    0x5E, 0xA3, 0xA5, 0xB7, 0xA9, 0xA7, 0xB6, 0xBC, 0xBD, 0xBE, 0x5B, 0x5D, 0xAF, 0xA8, 0xB4, 0xD7,
+    0x7B, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0xAD, 0xF4, 0xF6, 0xF2, 0xF3, 0xF5,
+    0x7D, 0x4A, 0x4B, 0x4C, 0x4D, 0x4E, 0x4F, 0x50, 0x51, 0x52, 0xB9, 0xFB, 0xFC, 0xF9, 0xFA, 0xFF,
+    0x5C, 0xF7, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58, 0x59, 0x5A, 0xB2, 0xD4, 0xD6, 0xD2, 0xD3, 0xD5,
+    0x30, 0x31, 0x32, 0x33, 0x34, 0x35, 0x36, 0x37, 0x38, 0x39, 0xB3, 0xDB, 0xDC, 0xD9, 0xDA, 0x9F,
+];
+
+const LATIN1_TO_CP037: [u8; 256] = invert(&CP037_TO_LATIN1);
+
+const fn invert(table: &[u8; 256]) -> [u8; 256] {
+    let mut inverse = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        inverse[table[i] as usize] = i as u8;
+        i += 1;
+    }
+    inverse
 }
@@ -1,3 +1,4 @@
+use crate::picture::{Category, Usage};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq, Clone)]
@@ -8,15 +9,102 @@ pub enum Error {
     #[error("parse error: invalid character '{invalid_char}' at index {index}")]
     ParseError { invalid_char: char, index: usize },
 
+    #[error("parse error: invalid byte 0x{byte:02X} at index {index}")]
+    InvalidByte { byte: u8, index: usize },
+
     #[error("value overflowed during conversion: {0}")]
     OverflowError(String),
 
     #[error("scale ({0}) is too large or invalid for internal representation")]
     InvalidScale(usize),
 
-    #[error("invalid field format string provided: {0}")]
-    InvalidFormatString(String),
+    #[error("invalid field format string '{format}' at column {column}: {reason}")]
+    InvalidFormatString {
+        format: String,
+        column: usize,
+        reason: &'static str,
+    },
+
+    #[error("value needs {required} positions but the field only has {available}")]
+    FieldTooNarrow { required: usize, available: usize },
+
+    #[error("usage {0} is not supported for overpunch fields")]
+    UnsupportedUsage(Usage),
+
+    #[error("picture describes {0} data, not a number")]
+    NonNumericPicture(Category),
+
+    #[error("invalid copybook at line {line}: {reason}")]
+    InvalidCopybook { line: usize, reason: String },
+
+    #[error("field {field} at offset {offset}: {source}")]
+    FieldError {
+        field: String,
+        offset: usize,
+        source: Box<Error>,
+    },
+
+    #[error("numeric fields need a number, not text")]
+    ExpectedNumber,
+
+    #[error("alphanumeric fields need text, not a number")]
+    ExpectedText,
+
+    #[error("record has no value for field {0}")]
+    MissingField(String),
+
+    #[error("record is {actual} bytes long but the layout needs {required}")]
+    RecordTooShort { required: usize, actual: usize },
+
+    #[error("invalid descriptor word at offset {offset}: {reason}")]
+    InvalidDescriptor { offset: u64, reason: &'static str },
+
+    #[error("input ends at offset {offset} with {available} of {required} bytes")]
+    TruncatedRecord {
+        offset: u64,
+        required: usize,
+        available: usize,
+    },
+
+    #[error("I/O error: {message}")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+
+    #[error("field has {actual} digits but its picture allows {allowed}")]
+    TooManyDigits { allowed: usize, actual: usize },
+
+    #[error("negative value for a picture without a sign")]
+    UnexpectedSign,
+
+    #[error("unsigned value for a field that requires a sign")]
+    MissingSign,
+
+    #[error("{value} has more than {decimals} decimal places")]
+    PrecisionLoss { value: String, decimals: usize },
+
+    #[error("invalid encoding table at '{symbol}': {reason}")]
+    InvalidEncodingTable { symbol: char, reason: &'static str },
+
+    #[error("no encoding is named `{0}`")]
+    UnknownEncoding(String),
+
+    #[error("an encoding named `{0}` is already registered")]
+    DuplicateEncoding(String),
 
     #[error("character '{0}' is not supported by the specified encoding")]
     UnsupportedCharacter(char),
+
+    #[error("byte 0x{0:02X} is not supported by the specified encoding")]
+    UnsupportedByte(u8),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
 }
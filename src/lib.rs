@@ -1,61 +1,135 @@
 use rust_decimal::Decimal;
 
+#[cfg(feature = "bigint")]
+pub mod bigint;
+pub mod binary;
+pub mod copybook;
 mod core;
+pub mod dataset;
+pub mod detect;
 pub mod encoding;
 pub mod error;
-
-pub use encoding::{Ebcdic, Encoding, Sign};
+pub mod options;
+pub mod packed;
+pub mod picture;
+pub mod record;
+pub mod registry;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+pub use encoding::{
+    Ascii, ByteEncoding, CodePage, Ebcdic, Encoding, EncodingTable, IbmI, MicroFocus, Ncr, Sign,
+    TableEncoding, Unisys, ZonedEbcdic,
+};
 pub use error::Error;
+pub use options::{
+    Adjustments, BinaryOptions, BlankPolicy, ByteOrder, Extraction, FormatOptions, Normalization,
+    OverflowPolicy, PackedSign, ParseOptions, RoundingMode, SignCheck, SignPosition, Truncation,
+    UnsignedPolicy, ZeroSignPolicy, ZonedSign,
+};
+pub use picture::{Category, Picture, Usage};
 
 static EBCDIC_INSTANCE: Ebcdic = Ebcdic;
+static ZONED_EBCDIC_INSTANCE: ZonedEbcdic = ZonedEbcdic;
 
 pub fn extract(raw: &str, decimals: usize) -> Result<Decimal, Error> {
-    core::extract_with_encoding(raw, decimals, &EBCDIC_INSTANCE)
+    core::extract_with_encoding(raw, decimals, &EBCDIC_INSTANCE, &ParseOptions::default())
+}
+
+pub fn extract_with_options(
+    raw: &str,
+    decimals: usize,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    core::extract_with_encoding(raw, decimals, options.encoding, options)
+}
+
+/// Like `extract_with_options`, but reports how `options.normalization` changed the input and
+/// returns `None` for blank fields read as missing.
+pub fn extract_with_report(
+    raw: &str,
+    decimals: usize,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    core::extract_reported(raw, decimals, options.encoding, options)
+}
+
+/// Like `extract`, but an empty field or one of only spaces or low-values yields `None`.
+pub fn extract_opt(raw: &str, decimals: usize) -> Result<Option<Decimal>, Error> {
+    core::extract_opt(raw, decimals, &EBCDIC_INSTANCE, &ParseOptions::default())
+}
+
+/// Like `extract_opt`, also treating `options.normalization.null_sentinel` as missing. Blank
+/// and low-value fields are only read otherwise when their policy is set to `Zero`.
+pub fn extract_opt_with_options(
+    raw: &str,
+    decimals: usize,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
+    core::extract_opt(raw, decimals, options.encoding, options)
 }
 
 pub fn format(value: Decimal, decimals: usize) -> Result<String, Error> {
-    // Special case for zero values
-    if value.is_zero() {
-        // Check for negative zero flag set in the test environment
-        let is_negative_zero = std::env::var("HANDLE_NEGATIVE_ZERO").unwrap_or_default() == "true";
-
-        if decimals == 0 {
-            // For zero with no decimals
-            return if is_negative_zero {
-                Ok("}".to_string()) // Negative zero
-            } else {
-                Ok("{".to_string()) // Positive zero
-            };
-        } else {
-            // For decimal zeros (e.g., 0.00 or -0.00)
-            let mut result = String::new();
-            for _ in 0..decimals {
-                result.push('0');
-            }
-
-            // Add the appropriate sign encoding
-            if is_negative_zero {
-                result.push('}'); // Negative
-            } else {
-                result.push('{'); // Positive
-            }
-
-            return Ok(result);
-        }
-    }
-
-    // For non-zero values, use the normal encoding logic
-    core::format_with_encoding(value, decimals, &EBCDIC_INSTANCE)
+    core::format_with_encoding(value, decimals, &EBCDIC_INSTANCE, &FormatOptions::default())
+}
+
+pub fn format_with_options(
+    value: Decimal,
+    decimals: usize,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    core::format_with_encoding(value, decimals, options.encoding, options)
 }
 
 pub fn convert_from_signed_format(value: &str, field_format: &str) -> Result<Decimal, Error> {
-    let decimals = parse_format(field_format)?;
-    core::extract_with_encoding(value, decimals, &EBCDIC_INSTANCE)
+    convert_from_signed_format_with_options(value, field_format, &ParseOptions::default())
+}
+
+pub fn convert_from_signed_format_with_options(
+    value: &str,
+    field_format: &str,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    let picture = Picture::parse(field_format)?;
+    core::extract_with_picture(value, &picture, options.encoding, options)
+}
+
+pub fn convert_from_signed_format_with_report(
+    value: &str,
+    field_format: &str,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    let picture = Picture::parse(field_format)?;
+    core::extract_with_picture_reported(value, &picture, options.encoding, options)
+}
+
+pub fn convert_from_signed_format_opt(
+    value: &str,
+    field_format: &str,
+) -> Result<Option<Decimal>, Error> {
+    convert_from_signed_format_opt_with_options(value, field_format, &ParseOptions::default())
+}
+
+pub fn convert_from_signed_format_opt_with_options(
+    value: &str,
+    field_format: &str,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
+    let picture = Picture::parse(field_format)?;
+    core::extract_with_picture_opt(value, &picture, options.encoding, options)
 }
 
 pub fn convert_to_signed_format(value: Decimal, field_format: &str) -> Result<String, Error> {
-    let decimals = parse_format(field_format)?;
-    core::format_with_encoding(value, decimals, &EBCDIC_INSTANCE)
+    convert_to_signed_format_with_options(value, field_format, &FormatOptions::default())
+}
+
+pub fn convert_to_signed_format_with_options(
+    value: Decimal,
+    field_format: &str,
+    options: &FormatOptions,
+) -> Result<String, Error> {
+    let picture = Picture::parse(field_format)?;
+    core::format_with_picture(value, &picture, options.encoding, options)
 }
 
 pub fn extract_with_encoding<E: Encoding>(
@@ -63,7 +137,7 @@ pub fn extract_with_encoding<E: Encoding>(
     decimals: usize,
     encoding: &E,
 ) -> Result<Decimal, Error> {
-    core::extract_with_encoding(raw, decimals, encoding)
+    core::extract_with_encoding(raw, decimals, encoding, &ParseOptions::default())
 }
 
 pub fn format_with_encoding<E: Encoding>(
@@ -71,7 +145,7 @@ pub fn format_with_encoding<E: Encoding>(
     decimals: usize,
     encoding: &E,
 ) -> Result<String, Error> {
-    core::format_with_encoding(value, decimals, encoding)
+    core::format_with_encoding(value, decimals, encoding, &FormatOptions::default())
 }
 
 pub fn extract_with_dyn_encoding(
@@ -79,7 +153,7 @@ pub fn extract_with_dyn_encoding(
     decimals: usize,
     encoding: &dyn Encoding,
 ) -> Result<Decimal, Error> {
-    core::extract_with_encoding(raw, decimals, encoding)
+    core::extract_with_encoding(raw, decimals, encoding, &ParseOptions::default())
 }
 
 pub fn format_with_dyn_encoding(
@@ -87,48 +161,70 @@ pub fn format_with_dyn_encoding(
     decimals: usize,
     encoding: &dyn Encoding,
 ) -> Result<String, Error> {
-    core::format_with_encoding(value, decimals, encoding)
-}
-
-fn parse_format(field_format: &str) -> Result<usize, Error> {
-    // Check for pattern like s9(n)v9(m) or 9(n)v9(m)
-    if field_format.contains('(') && field_format.contains(')') {
-        if let Some(pos) = field_format.find(['v', 'V']) {
-            // For strings with decimal point marker
-            let decimal_part = &field_format[pos + 1..];
-
-            // Parse the decimal places by finding 9(n) pattern
-            if let Some(start) = decimal_part.find("9(") {
-                let end = decimal_part
-                    .find(')')
-                    .ok_or_else(|| Error::InvalidFormatString(field_format.to_string()))?;
-                if start + 2 < end {
-                    let count_str = &decimal_part[start + 2..end];
-                    if let Ok(count) = count_str.parse::<usize>() {
-                        return Ok(count);
-                    }
-                }
-            } else if decimal_part.chars().all(|c| c == '9') {
-                // Simple case: just a sequence of 9s after v
-                return Ok(decimal_part.len());
-            } else if decimal_part.is_empty() {
-                return Ok(0);
-            }
-        } else {
-            // No decimal point, but still need to check if format is valid
-            if field_format.starts_with('s') || field_format.starts_with('9') {
-                return Ok(0); // Valid format but no decimals
-            }
-        }
-    } else if let Some(pos) = field_format.find(['v', 'V']) {
-        // Simple case without parentheses: s9v99 or 9v99
-        let decimal_part = &field_format[pos + 1..];
-        if decimal_part.chars().all(|c| c == '9') {
-            return Ok(decimal_part.len());
-        } else if decimal_part.is_empty() {
-            return Ok(0);
-        }
-    }
-
-    Err(Error::InvalidFormatString(field_format.to_string()))
+    core::format_with_encoding(value, decimals, encoding, &FormatOptions::default())
+}
+
+pub fn extract_bytes(raw: &[u8], decimals: usize) -> Result<Decimal, Error> {
+    core::extract_bytes_with_encoding(
+        raw,
+        decimals,
+        &ZONED_EBCDIC_INSTANCE,
+        &ParseOptions::default(),
+    )
+}
+
+pub fn extract_bytes_opt(raw: &[u8], decimals: usize) -> Result<Option<Decimal>, Error> {
+    core::extract_bytes_opt(
+        raw,
+        decimals,
+        &ZONED_EBCDIC_INSTANCE,
+        &ParseOptions::default(),
+    )
+}
+
+pub fn format_into(buf: &mut [u8], value: Decimal, decimals: usize) -> Result<(), Error> {
+    core::format_into_with_encoding(
+        buf,
+        value,
+        decimals,
+        &ZONED_EBCDIC_INSTANCE,
+        &FormatOptions::default(),
+    )
+}
+
+pub fn extract_bytes_with_encoding<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Decimal, Error> {
+    core::extract_bytes_with_encoding(raw, decimals, encoding, options)
+}
+
+pub fn extract_bytes_opt_with_encoding<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Option<Decimal>, Error> {
+    core::extract_bytes_opt(raw, decimals, encoding, options)
+}
+
+pub fn extract_bytes_with_report<E: ByteEncoding + ?Sized>(
+    raw: &[u8],
+    decimals: usize,
+    encoding: &E,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    core::extract_bytes_reported(raw, decimals, encoding, options)
+}
+
+pub fn format_into_with_encoding<E: ByteEncoding + ?Sized>(
+    buf: &mut [u8],
+    value: Decimal,
+    decimals: usize,
+    encoding: &E,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    core::format_into_with_encoding(buf, value, decimals, encoding, options)
 }
@@ -0,0 +1,271 @@
+use crate::encoding::{ByteEncoding, CodePage, Ebcdic, Encoding, ZonedEbcdic};
+use rust_decimal::Decimal;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OverflowPolicy {
+    /// Fail with `Error::FieldTooNarrow` when a value does not fit the declared width.
+    #[default]
+    Reject,
+    /// Drop high-order digits that do not fit, as COBOL `MOVE` does.
+    Truncate,
+}
+
+/// How values with more decimal places than the field holds are rounded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum RoundingMode {
+    /// Drop the excess digits, as COBOL does without `ROUNDED`.
+    Truncate,
+    /// Round halves away from zero, as COBOL `ROUNDED` does.
+    HalfUp,
+    /// Round halves to the nearest even digit (banker's rounding).
+    #[default]
+    HalfEven,
+    /// Round toward positive infinity.
+    Ceiling,
+    /// Round toward negative infinity.
+    Floor,
+    /// Fail with `Error::PrecisionLoss` instead of dropping non-zero digits.
+    Reject,
+}
+
+/// How the sign of a zero result is encoded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ZeroSignPolicy {
+    /// Keep the sign of the input, including negative values that round to zero.
+    #[default]
+    Preserve,
+    /// Encode every zero as positive.
+    AlwaysPositive,
+    /// Encode zero as negative only when the input is itself a negative zero.
+    NegativeZeroOnly,
+}
+
+/// Where the sign of a zoned decimal field is stored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SignPosition {
+    /// `SIGN IS TRAILING`, the COBOL default: the last digit is overpunched.
+    #[default]
+    TrailingOverpunch,
+    /// `SIGN IS LEADING`: the first digit is overpunched.
+    LeadingOverpunch,
+    /// `SIGN IS LEADING SEPARATE CHARACTER`: a `+` or `-` before the digits.
+    LeadingSeparate,
+    /// `SIGN IS TRAILING SEPARATE CHARACTER`: a `+` or `-` after the digits.
+    TrailingSeparate,
+}
+
+impl SignPosition {
+    pub fn is_separate(self) -> bool {
+        matches!(
+            self,
+            SignPosition::LeadingSeparate | SignPosition::TrailingSeparate
+        )
+    }
+}
+
+/// Whether extraction checks the sign of a value against its picture.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SignCheck {
+    /// Accept a negative value even when the picture has no `S`.
+    #[default]
+    Lenient,
+    /// Fail with `Error::UnexpectedSign` when a picture without `S` reads as negative.
+    Strict,
+}
+
+/// What extraction does with a signed field whose sign position holds an unsigned digit, such
+/// as a plain `'5'` or the `F` zone.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum UnsignedPolicy {
+    /// Read the value as positive.
+    #[default]
+    Accept,
+    /// Fail with `Error::MissingSign`.
+    Reject,
+    /// Read the value as positive and set `Adjustments::unsigned`.
+    Flag,
+}
+
+/// How the sign position of zoned decimal fields is written for positive values.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ZonedSign {
+    /// Overpunch every positive value, as `{`/`A`-`I` or the `C` zone.
+    #[default]
+    Preferred,
+    /// Leave the last digit plain, or in the `F` zone, for pictures without `S`.
+    Unsigned,
+}
+
+/// The sign nibble written for positive packed decimal values.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PackedSign {
+    /// `C` for positive values and `D` for negative ones.
+    #[default]
+    Preferred,
+    /// `F` for positive values, as written for unsigned pictures, and `D` for negative ones.
+    Unsigned,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ByteOrder {
+    /// Mainframe byte order.
+    #[default]
+    BigEndian,
+    LittleEndian,
+}
+
+/// How binary (`COMP`/`COMP-4`) fields are limited, after the `TRUNC` compiler option.
+/// `COMP-5` fields always behave as `Binary`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Truncation {
+    /// `TRUNC(STD)`: values are limited to the digits declared by the picture.
+    #[default]
+    Standard,
+    /// `TRUNC(BIN)`: values may use the full range of the binary storage.
+    Binary,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BinaryOptions {
+    pub truncation: Truncation,
+    pub byte_order: ByteOrder,
+}
+
+/// What a field holding nothing but spaces, low-values or high-values reads as.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum BlankPolicy {
+    /// Decode the field like any other, which fails unless spaces are read as zeros.
+    #[default]
+    Reject,
+    /// Read the field as zero.
+    Zero,
+    /// Read the field as having no value. Functions returning a plain `Decimal` fail with
+    /// `Error::EmptyField`.
+    Missing,
+}
+
+/// Clean-up applied to zoned input before it is decoded. Every switch is off by default, so
+/// parsing stays strict unless one is turned on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Normalization {
+    /// Ignore spaces before and after the digits.
+    pub trim: bool,
+    /// Read any other space as a zero digit.
+    pub spaces_as_zeros: bool,
+    /// A field of only spaces.
+    pub blank: BlankPolicy,
+    /// A field of only low-values (`0x00`).
+    pub low_values: BlankPolicy,
+    /// A field of only high-values (`0xFF`).
+    pub high_values: BlankPolicy,
+    /// A value that marks the whole field as missing, such as `"99999"`. Byte fields are
+    /// compared after encoding it with `ParseOptions::code_page`.
+    pub null_sentinel: Option<&'static str>,
+}
+
+impl Normalization {
+    /// Every switch on: blank fields read as zero and low-values or high-values as missing.
+    pub fn lenient() -> Normalization {
+        Normalization {
+            trim: true,
+            spaces_as_zeros: true,
+            blank: BlankPolicy::Zero,
+            low_values: BlankPolicy::Missing,
+            high_values: BlankPolicy::Missing,
+            null_sentinel: None,
+        }
+    }
+}
+
+/// The normalisation steps that changed a field, and anything flagged while reading it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Adjustments {
+    pub trimmed: bool,
+    pub spaces_as_zeros: bool,
+    pub blank: bool,
+    pub low_values: bool,
+    pub high_values: bool,
+    pub null_sentinel: bool,
+    /// The sign position held an unsigned digit and `UnsignedPolicy::Flag` is set.
+    pub unsigned: bool,
+}
+
+impl Adjustments {
+    /// True when the field was decoded exactly as given.
+    pub fn is_empty(&self) -> bool {
+        *self == Adjustments::default()
+    }
+}
+
+/// A value read with `ParseOptions::normalization`, along with what was done to read it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Extraction {
+    /// `None` for a blank field whose policy is `BlankPolicy::Missing`.
+    pub value: Option<Decimal>,
+    pub adjustments: Adjustments,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+    /// Encoding used by the `*_with_options` functions.
+    pub encoding: &'static dyn Encoding,
+    /// Encoding of zoned decimal fields in byte records.
+    pub byte_encoding: &'static dyn ByteEncoding,
+    /// Character set of alphanumeric fields in byte records.
+    pub code_page: CodePage,
+    pub sign_position: SignPosition,
+    pub binary: BinaryOptions,
+    pub sign_check: SignCheck,
+    pub unsigned: UnsignedPolicy,
+    /// Lenient handling of padded and blank zoned fields; strict by default.
+    pub normalization: Normalization,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            encoding: &Ebcdic,
+            byte_encoding: &ZonedEbcdic,
+            code_page: CodePage::default(),
+            sign_position: SignPosition::default(),
+            binary: BinaryOptions::default(),
+            sign_check: SignCheck::default(),
+            unsigned: UnsignedPolicy::default(),
+            normalization: Normalization::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FormatOptions {
+    /// Encoding used by the `*_with_options` functions.
+    pub encoding: &'static dyn Encoding,
+    /// Encoding of zoned decimal fields in byte records.
+    pub byte_encoding: &'static dyn ByteEncoding,
+    /// Character set of alphanumeric fields in byte records.
+    pub code_page: CodePage,
+    pub overflow: OverflowPolicy,
+    pub rounding: RoundingMode,
+    pub zero_sign: ZeroSignPolicy,
+    pub sign_position: SignPosition,
+    pub zoned_sign: ZonedSign,
+    pub packed_sign: PackedSign,
+    pub binary: BinaryOptions,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            encoding: &Ebcdic,
+            byte_encoding: &ZonedEbcdic,
+            code_page: CodePage::default(),
+            overflow: OverflowPolicy::default(),
+            rounding: RoundingMode::default(),
+            zero_sign: ZeroSignPolicy::default(),
+            sign_position: SignPosition::default(),
+            zoned_sign: ZonedSign::default(),
+            packed_sign: PackedSign::default(),
+            binary: BinaryOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::core;
+use crate::encoding::Sign;
+use crate::error::Error;
+use crate::options::{Adjustments, Extraction, FormatOptions, PackedSign, ParseOptions};
+use crate::picture::{Picture, Usage};
+use rust_decimal::Decimal;
+
+pub fn unpack(raw: &[u8], decimals: usize) -> Result<Decimal, Error> {
+    let (mantissa, sign) = decode(raw)?;
+    core::make_decimal(mantissa, sign, decimals)
+}
+
+pub fn pack_into(
+    buf: &mut [u8],
+    value: Decimal,
+    decimals: usize,
+    options: &FormatOptions,
+) -> Result<(), Error> {
+    let (digits, sign) = core::decimal_digits(value, decimals, options)?;
+    let digits = core::fit_width(digits, capacity(buf.len()), options.overflow)?;
+    encode(buf, &digits, sign, options.packed_sign)
+}
+
+/// Unpacks a field declared by `picture`, failing with `Error::TooManyDigits` when the value
+/// has more significant digits than the picture declares.
+pub fn unpack_with_picture(raw: &[u8], picture: &Picture) -> Result<Decimal, Error> {
+    Ok(unpack_signed(raw, picture)?.0)
+}
+
+/// Unpacks a field declared by `picture`, checking its sign against `options`. An `F` sign
+/// nibble on a signed picture is reported when `UnsignedPolicy::Flag` is set.
+pub fn unpack_with_report(
+    raw: &[u8],
+    picture: &Picture,
+    options: &ParseOptions,
+) -> Result<Extraction, Error> {
+    let (value, sign) = unpack_signed(raw, picture)?;
+    core::check_picture_sign(Some(value), picture, options)?;
+    Ok(Extraction {
+        value: Some(value),
+        adjustments: Adjustments {
+            unsigned: core::check_unsigned(sign, picture.is_signed(), options)?,
+            ..Adjustments::default()
+        },
+    })
+}
+
+fn unpack_signed(raw: &[u8], picture: &Picture) -> Result<(Decimal, Sign), Error> {
+    core::check_usage(picture, Usage::PackedDecimal)?;
+    let (mantissa, sign) = decode(raw)?;
+    let actual = if mantissa == 0 {
+        0
+    } else {
+        mantissa.ilog10() as usize + 1
+    };
+    if actual > picture.digits() {
+        return Err(Error::TooManyDigits {
+            allowed: picture.digits(),
+            actual,
+        });
+    }
+    let value = core::make_decimal(mantissa, sign, core::picture_decimals(picture))?;
+    let value = core::apply_picture_scaling(value, picture, || format!("{:02X?}", raw))?;
+    Ok((value, sign))
+}
+
+pub fn pack_with_picture(
+    value: Decimal,
+    picture: &Picture,
+    options: &FormatOptions,
+) -> Result<Vec<u8>, Error> {
+    core::check_usage(picture, Usage::PackedDecimal)?;
+    let (digits, sign) = core::picture_digits(value, picture, options)?;
+    let mut buf = vec![0u8; picture.width()];
+    encode(&mut buf, &digits, sign, options.packed_sign)?;
+    Ok(buf)
+}
+
+// Every byte holds two digits except the last, whose low nibble is the sign.
+fn capacity(len: usize) -> usize {
+    (len * 2).saturating_sub(1)
+}
+
+fn decode(raw: &[u8]) -> Result<(u128, Sign), Error> {
+    let (&last, rest) = match raw.split_last() {
+        Some(split) => split,
+        None => return Err(Error::EmptyField),
+    };
+
+    let overflow = || Error::OverflowError(format!("{:02X?}", raw));
+    let mut mantissa: u128 = 0;
+    for (index, &byte) in rest.iter().enumerate() {
+        for nibble in [byte >> 4, byte & 0x0F] {
+            if nibble > 9 {
+                return Err(Error::InvalidByte { byte, index });
+            }
+            mantissa = core::push_digit(mantissa, nibble).ok_or_else(overflow)?;
+        }
+    }
+
+    let index = rest.len();
+    let digit = last >> 4;
+    if digit > 9 {
+        return Err(Error::InvalidByte { byte: last, index });
+    }
+    mantissa = core::push_digit(mantissa, digit).ok_or_else(overflow)?;
+
+    // A, C and E are positive, B and D are negative and F is unsigned
+    let sign = match last & 0x0F {
+        0xA | 0xC | 0xE => Sign::Positive,
+        0xF => Sign::Unsigned,
+        0xB | 0xD => Sign::Negative,
+        _ => return Err(Error::InvalidByte { byte: last, index }),
+    };
+
+    Ok((mantissa, sign))
+}
+
+fn encode(buf: &mut [u8], digits: &str, sign: Sign, packed_sign: PackedSign) -> Result<(), Error> {
+    let sign_nibble = match (sign, packed_sign) {
+        (Sign::Negative, _) => 0x0D,
+        (Sign::Positive, PackedSign::Preferred) => 0x0C,
+        (Sign::Positive, PackedSign::Unsigned) | (Sign::Unsigned, _) => 0x0F,
+    };
+
+    // Right-align the digits so the last one shares a byte with the sign
+    let mut nibbles = vec![0u8; capacity(buf.len()) - digits.len()];
+    nibbles.extend(digits.bytes().map(|d| d - b'0'));
+    nibbles.push(sign_nibble);
+
+    for (out, pair) in buf.iter_mut().zip(nibbles.chunks(2)) {
+        *out = (pair[0] << 4) | pair[1];
+    }
+    Ok(())
+}
@@ -0,0 +1,412 @@
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+const MAX_DIGITS: usize = 38;
+const MAX_BINARY_DIGITS: usize = 18;
+const MAX_LENGTH: usize = 1 << 27;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Usage {
+    #[default]
+    Display,
+    PackedDecimal,
+    Binary,
+    NativeBinary,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            Usage::Display => "DISPLAY",
+            Usage::PackedDecimal => "COMP-3",
+            Usage::Binary => "COMP",
+            Usage::NativeBinary => "COMP-5",
+        };
+        f.write_str(keyword)
+    }
+}
+
+/// The class of data a picture describes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Category {
+    #[default]
+    Numeric,
+    /// Only `A` positions.
+    Alphabetic,
+    /// Any mix of `X`, `A` and `9` positions containing at least one `X`.
+    Alphanumeric,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Numeric => "numeric",
+            Category::Alphabetic => "alphabetic",
+            Category::Alphanumeric => "alphanumeric",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A parsed COBOL `PICTURE` clause such as `S9(7)V99`, `PIC 9(5) COMP-3` or `X(10)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Picture {
+    category: Category,
+    // Character positions of an alphabetic or alphanumeric picture
+    length: usize,
+    signed: bool,
+    integer_digits: usize,
+    fraction_digits: usize,
+    scaling: Scaling,
+    usage: Usage,
+}
+
+// `P` positions are not stored; they only move the implied decimal point.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Scaling {
+    None,
+    Leading(usize),
+    Trailing(usize),
+}
+
+impl Picture {
+    pub fn parse(input: &str) -> Result<Picture, Error> {
+        Parser { input }.parse()
+    }
+
+    pub fn category(&self) -> Category {
+        self.category
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        self.category == Category::Numeric
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    pub fn integer_digits(&self) -> usize {
+        self.integer_digits
+    }
+
+    pub fn fraction_digits(&self) -> usize {
+        self.fraction_digits
+    }
+
+    pub fn digits(&self) -> usize {
+        self.integer_digits + self.fraction_digits
+    }
+
+    /// The power of ten the stored digits are divided by, including any `P` scaling.
+    /// Trailing `P` positions yield a negative scale.
+    pub fn scale(&self) -> i32 {
+        match self.scaling {
+            Scaling::None => self.fraction_digits as i32,
+            Scaling::Leading(p) => (p + self.fraction_digits) as i32,
+            Scaling::Trailing(p) => -(p as i32),
+        }
+    }
+
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+
+    /// The number of bytes the field occupies in a record.
+    pub fn width(&self) -> usize {
+        if !self.is_numeric() {
+            return self.length;
+        }
+        let digits = self.digits();
+        match self.usage {
+            Usage::Display => digits,
+            Usage::PackedDecimal => digits / 2 + 1,
+            Usage::Binary | Usage::NativeBinary => match digits {
+                0..=4 => 2,
+                5..=9 => 4,
+                _ => 8,
+            },
+        }
+    }
+}
+
+/// Writes the picture in a normalised form, e.g. `S9(5)V9(2) COMP-3`.
+impl fmt::Display for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.category {
+            Category::Alphabetic => return write!(f, "A({})", self.length),
+            Category::Alphanumeric => return write!(f, "X({})", self.length),
+            Category::Numeric => {}
+        }
+        if self.signed {
+            f.write_str("S")?;
+        }
+        if self.integer_digits > 0 {
+            write!(f, "9({})", self.integer_digits)?;
+        }
+        match self.scaling {
+            Scaling::Leading(p) => write!(f, "VP({p})")?,
+            Scaling::Trailing(p) => write!(f, "P({p})")?,
+            Scaling::None if self.fraction_digits > 0 => f.write_str("V")?,
+            Scaling::None => {}
+        }
+        if self.fraction_digits > 0 {
+            write!(f, "9({})", self.fraction_digits)?;
+        }
+        if self.usage != Usage::Display {
+            write!(f, " {}", self.usage)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Picture {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Picture::parse(s)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl Parser<'_> {
+    fn parse(&self) -> Result<Picture, Error> {
+        let mut words = self.words().into_iter().peekable();
+
+        if let Some(&(_, word)) = words.peek() {
+            if word.eq_ignore_ascii_case("PIC") || word.eq_ignore_ascii_case("PICTURE") {
+                words.next();
+                if let Some(&(_, word)) = words.peek() {
+                    if word.eq_ignore_ascii_case("IS") {
+                        words.next();
+                    }
+                }
+            }
+        }
+
+        let (offset, string) = match words.next() {
+            Some(word) => word,
+            None => return Err(self.error(self.input.len(), "missing picture character-string")),
+        };
+        let mut picture = self.parse_string(offset, string.strip_suffix('.').unwrap_or(string))?;
+
+        if let Some(&(_, word)) = words.peek() {
+            if word.eq_ignore_ascii_case("USAGE") {
+                words.next();
+                if let Some(&(_, word)) = words.peek() {
+                    if word.eq_ignore_ascii_case("IS") {
+                        words.next();
+                    }
+                }
+            }
+        }
+
+        if let Some((offset, word)) = words.next() {
+            picture.usage = match parse_usage(word.strip_suffix('.').unwrap_or(word)) {
+                Some(usage) => usage,
+                None => return Err(self.error(offset, "unsupported usage clause")),
+            };
+        }
+
+        if let Some((offset, _)) = words.next() {
+            return Err(self.error(offset, "unexpected text after picture clause"));
+        }
+
+        if !picture.is_numeric() && picture.usage != Usage::Display {
+            return Err(self.error(offset, "alphanumeric items must have DISPLAY usage"));
+        }
+        if picture.usage != Usage::Display
+            && picture.usage != Usage::PackedDecimal
+            && picture.digits() > MAX_BINARY_DIGITS
+        {
+            return Err(self.error(offset, "binary usage supports at most 18 digit positions"));
+        }
+
+        Ok(picture)
+    }
+
+    fn parse_string(&self, offset: usize, string: &str) -> Result<Picture, Error> {
+        let bytes = string.as_bytes();
+        let mut signed = false;
+        let mut seen_v = false;
+        let mut integer_digits = 0usize;
+        let mut fraction_digits = 0usize;
+        let mut leading_p = 0usize;
+        let mut trailing_p = 0usize;
+        let mut alphabetic = 0usize;
+        let mut alphanumeric = 0usize;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let at = offset + i;
+            let symbol = bytes[i].to_ascii_uppercase();
+            i += 1;
+
+            let count = if bytes.get(i) == Some(&b'(') {
+                let close = match bytes[i..].iter().position(|&b| b == b')') {
+                    Some(close) => i + close,
+                    None => return Err(self.error(offset + i, "unclosed repetition count")),
+                };
+                let digits = &string[i + 1..close];
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(self.error(offset + i + 1, "repetition count must be a number"));
+                }
+                let limit = match symbol {
+                    b'X' | b'A' => MAX_LENGTH,
+                    _ => MAX_DIGITS,
+                };
+                let count = match digits.parse::<usize>() {
+                    Ok(count) if count > 0 && count <= limit => count,
+                    _ => return Err(self.error(offset + i + 1, "repetition count out of range")),
+                };
+                i = close + 1;
+                count
+            } else {
+                1
+            };
+
+            let text = alphabetic + alphanumeric > 0;
+            match symbol {
+                b'X' | b'A' => {
+                    if signed || seen_v || leading_p + trailing_p > 0 {
+                        return Err(self.error(at, "X and A cannot be combined with S, V or P"));
+                    }
+                    // Digit positions seen so far turn out to be part of a text item
+                    alphanumeric += std::mem::take(&mut integer_digits);
+                    if symbol == b'X' {
+                        alphanumeric += count;
+                    } else {
+                        alphabetic += count;
+                    }
+                }
+                b'S' | b'V' | b'P' if text => {
+                    return Err(self.error(at, "S, V and P cannot be combined with X or A"));
+                }
+                b'S' => {
+                    if at != offset {
+                        return Err(self.error(at, "S must be the first symbol"));
+                    }
+                    if count != 1 {
+                        return Err(self.error(at, "S may appear only once"));
+                    }
+                    signed = true;
+                }
+                b'9' if text => alphanumeric += count,
+                b'9' => {
+                    if trailing_p > 0 {
+                        return Err(self.error(at, "digit positions cannot follow trailing P"));
+                    }
+                    if seen_v || leading_p > 0 {
+                        fraction_digits += count;
+                    } else {
+                        integer_digits += count;
+                    }
+                }
+                b'V' => {
+                    if seen_v {
+                        return Err(self.error(at, "V may appear only once"));
+                    }
+                    if count != 1 {
+                        return Err(self.error(at, "V may appear only once"));
+                    }
+                    if leading_p > 0 {
+                        return Err(self.error(at, "V cannot follow leading P positions"));
+                    }
+                    seen_v = true;
+                }
+                b'P' => {
+                    if integer_digits + fraction_digits == 0 {
+                        leading_p += count;
+                    } else if leading_p > 0 || seen_v {
+                        return Err(self.error(at, "P positions must be at one end of the picture"));
+                    } else {
+                        trailing_p += count;
+                    }
+                }
+                _ => return Err(self.error(at, "unsupported picture symbol")),
+            }
+        }
+
+        if alphabetic + alphanumeric > 0 {
+            let category = match alphanumeric {
+                0 => Category::Alphabetic,
+                _ => Category::Alphanumeric,
+            };
+            return Ok(Picture {
+                category,
+                length: alphabetic + alphanumeric,
+                signed: false,
+                integer_digits: 0,
+                fraction_digits: 0,
+                scaling: Scaling::None,
+                usage: Usage::Display,
+            });
+        }
+        if integer_digits + fraction_digits == 0 {
+            return Err(self.error(offset + bytes.len(), "picture has no digit positions"));
+        }
+        if integer_digits + fraction_digits > MAX_DIGITS {
+            return Err(self.error(offset, "picture exceeds 38 digit positions"));
+        }
+
+        let scaling = if leading_p > 0 {
+            Scaling::Leading(leading_p)
+        } else if trailing_p > 0 {
+            Scaling::Trailing(trailing_p)
+        } else {
+            Scaling::None
+        };
+
+        Ok(Picture {
+            category: Category::Numeric,
+            length: 0,
+            signed,
+            integer_digits,
+            fraction_digits,
+            scaling,
+            usage: Usage::Display,
+        })
+    }
+
+    fn words(&self) -> Vec<(usize, &str)> {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (index, c) in self.input.char_indices() {
+            match (c.is_whitespace(), start) {
+                (true, Some(s)) => {
+                    words.push((s, &self.input[s..index]));
+                    start = None;
+                }
+                (false, None) => start = Some(index),
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            words.push((s, &self.input[s..]));
+        }
+        words
+    }
+
+    fn error(&self, index: usize, reason: &'static str) -> Error {
+        Error::InvalidFormatString {
+            format: self.input.to_string(),
+            column: index + 1,
+            reason,
+        }
+    }
+}
+
+fn parse_usage(word: &str) -> Option<Usage> {
+    let usage = match word.to_ascii_uppercase().as_str() {
+        "DISPLAY" => Usage::Display,
+        "COMP" | "COMPUTATIONAL" | "COMP-4" | "COMPUTATIONAL-4" | "BINARY" => Usage::Binary,
+        "COMP-3" | "COMPUTATIONAL-3" | "PACKED-DECIMAL" => Usage::PackedDecimal,
+        "COMP-5" | "COMPUTATIONAL-5" => Usage::NativeBinary,
+        _ => return None,
+    };
+    Some(usage)
+}
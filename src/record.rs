@@ -0,0 +1,501 @@
+use crate::binary;
+use crate::copybook::{Field, RecordLayout, Value};
+use crate::core;
+use crate::error::Error;
+use crate::options::{FormatOptions, OverflowPolicy, ParseOptions};
+use crate::packed;
+use crate::picture::Usage;
+use rust_decimal::Decimal;
+
+/// The value of a single field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldValue {
+    Number(Decimal),
+    Text(String),
+}
+
+impl From<Decimal> for FieldValue {
+    fn from(value: Decimal) -> Self {
+        FieldValue::Number(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::Text(value)
+    }
+}
+
+/// Field values keyed by name, in layout order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    values: Vec<(String, FieldValue)>,
+}
+
+impl Record {
+    pub fn new() -> Record {
+        Record::default()
+    }
+
+    /// Looks up a value by field name, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.values
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Sets the value of a field, replacing any earlier value for the same name.
+    pub fn insert(&mut self, name: impl Into<String>, value: FieldValue) {
+        let name = name.into();
+        match self
+            .values
+            .iter_mut()
+            .find(|(field, _)| field.eq_ignore_ascii_case(&name))
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.values.push((name, value)),
+        }
+    }
+
+    /// The value of a numeric field, failing when it is missing or holds text.
+    pub fn number(&self, name: &str) -> Result<Decimal, Error> {
+        match self.get(name) {
+            Some(FieldValue::Number(value)) => Ok(*value),
+            Some(FieldValue::Text(_)) => Err(Error::ExpectedNumber),
+            None => Err(Error::MissingField(name.to_string())),
+        }
+    }
+
+    /// The value of an alphanumeric field, failing when it is missing or holds a number.
+    pub fn text(&self, name: &str) -> Result<&str, Error> {
+        match self.get(name) {
+            Some(FieldValue::Text(value)) => Ok(value),
+            Some(FieldValue::Number(_)) => Err(Error::ExpectedText),
+            None => Err(Error::MissingField(name.to_string())),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, FieldValue)> for Record {
+    fn from_iter<I: IntoIterator<Item = (S, FieldValue)>>(iter: I) -> Self {
+        let mut record = Record::new();
+        for (name, value) in iter {
+            record.insert(name, value);
+        }
+        record
+    }
+}
+
+/// A struct mapped onto a fixed-width text record, usually through
+/// `#[derive(OverpunchRecord)]` from the `overpunch_ng_derive` crate.
+pub trait OverpunchRecord: Sized {
+    fn layout() -> &'static RecordLayout;
+    fn from_values(values: &Record) -> Result<Self, Error>;
+    fn to_values(&self) -> Record;
+
+    fn from_record(record: &str) -> Result<Self, Error> {
+        Self::from_record_with_options(record, &ParseOptions::default())
+    }
+
+    fn from_record_with_options(record: &str, options: &ParseOptions) -> Result<Self, Error> {
+        let reader = Reader {
+            layout: Self::layout(),
+            options,
+        };
+        Self::from_values(&reader.decode_str(record)?)
+    }
+
+    fn to_record(&self) -> Result<String, Error> {
+        self.to_record_with_options(&FormatOptions::default())
+    }
+
+    fn to_record_with_options(&self, options: &FormatOptions) -> Result<String, Error> {
+        let writer = Writer {
+            layout: Self::layout(),
+            options,
+        };
+        writer.encode_str(&self.to_values())
+    }
+}
+
+/// Decodes fixed-width records into typed field values.
+///
+/// `FILLER` items and items that `REDEFINES` other storage are skipped; build a layout from
+/// the redefining fields to read the other view of the record.
+#[derive(Debug, Clone)]
+pub struct RecordDecoder {
+    layout: RecordLayout,
+    options: ParseOptions,
+}
+
+impl RecordDecoder {
+    pub fn new(layout: RecordLayout) -> RecordDecoder {
+        RecordDecoder::with_options(layout, ParseOptions::default())
+    }
+
+    pub fn with_options(layout: RecordLayout, options: ParseOptions) -> RecordDecoder {
+        RecordDecoder { layout, options }
+    }
+
+    pub fn layout(&self) -> &RecordLayout {
+        &self.layout
+    }
+
+    /// Decodes a record of raw bytes, reading zoned fields with `options.byte_encoding` and
+    /// alphanumeric fields with `options.code_page`.
+    pub fn decode_bytes(&self, record: &[u8]) -> Result<Record, Error> {
+        self.reader().decode_bytes(record)
+    }
+
+    /// Decodes a text record, reading zoned fields with `options.encoding`. Offsets count
+    /// characters rather than bytes.
+    pub fn decode_str(&self, record: &str) -> Result<Record, Error> {
+        self.reader().decode_str(record)
+    }
+
+    fn reader(&self) -> Reader<'_> {
+        Reader {
+            layout: &self.layout,
+            options: &self.options,
+        }
+    }
+}
+
+// Decoding against a borrowed layout, shared by `RecordDecoder` and `OverpunchRecord`
+struct Reader<'a> {
+    layout: &'a RecordLayout,
+    options: &'a ParseOptions,
+}
+
+impl Reader<'_> {
+    fn decode_bytes(&self, record: &[u8]) -> Result<Record, Error> {
+        check_length(self.layout, record.len())?;
+        self.decode(|field| {
+            let raw = &record[field.offset..field.offset + field.length()];
+            self.decode_bytes_field(field, raw)
+        })
+    }
+
+    fn decode_str(&self, record: &str) -> Result<Record, Error> {
+        let starts: Vec<usize> = record
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(record.len()))
+            .collect();
+        check_length(self.layout, starts.len() - 1)?;
+        self.decode(|field| {
+            let raw = &record[starts[field.offset]..starts[field.offset + field.length()]];
+            self.decode_str_field(field, raw)
+        })
+    }
+
+    fn decode<F>(&self, mut decode_field: F) -> Result<Record, Error>
+    where
+        F: FnMut(&Field) -> Result<FieldValue, Error>,
+    {
+        let mut record = Record::new();
+        for field in self.layout.fields() {
+            if field.is_filler() || field.redefines {
+                continue;
+            }
+            let value = decode_field(field).map_err(|source| Error::FieldError {
+                field: field.name.clone(),
+                offset: field.offset,
+                source: Box::new(source),
+            })?;
+            record.values.push((field.name.clone(), value));
+        }
+        Ok(record)
+    }
+
+    fn decode_bytes_field(&self, field: &Field, raw: &[u8]) -> Result<FieldValue, Error> {
+        let picture = &field.picture;
+        if !picture.is_numeric() {
+            return Ok(FieldValue::Text(self.options.code_page.decode(raw)));
+        }
+        let value = match picture.usage() {
+            Usage::Display => core::extract_bytes_with_picture(
+                raw,
+                picture,
+                self.options.byte_encoding,
+                &self.field_options(field),
+            )?,
+            Usage::PackedDecimal => {
+                core::required(packed::unpack_with_report(raw, picture, self.options)?)?
+            }
+            Usage::Binary | Usage::NativeBinary => {
+                binary::read_with_picture(raw, picture, self.options)?
+            }
+        };
+        Ok(FieldValue::Number(value))
+    }
+
+    fn decode_str_field(&self, field: &Field, raw: &str) -> Result<FieldValue, Error> {
+        let picture = &field.picture;
+        if !picture.is_numeric() {
+            return Ok(FieldValue::Text(raw.to_string()));
+        }
+        let value = core::extract_with_picture(
+            raw,
+            picture,
+            self.options.encoding,
+            &self.field_options(field),
+        )?;
+        Ok(FieldValue::Number(value))
+    }
+
+    // The sign position comes from the field's own SIGN clause
+    fn field_options(&self, field: &Field) -> ParseOptions {
+        ParseOptions {
+            sign_position: field.sign_position,
+            ..*self.options
+        }
+    }
+}
+
+/// Writes fixed-width records from field values.
+///
+/// Fields without a value in the record are initialised the way COBOL `INITIALIZE` would:
+/// from their `VALUE` clause when they have one, otherwise spaces for alphanumeric items and
+/// zero for numeric ones. `FILLER` items only ever take their `VALUE` or spaces. Items that
+/// `REDEFINES` other storage are written only when the record has a value for them.
+#[derive(Debug, Clone)]
+pub struct RecordEncoder {
+    layout: RecordLayout,
+    options: FormatOptions,
+}
+
+// What ends up in a field's storage
+enum Content<'a> {
+    Number(Decimal),
+    Text(&'a str),
+    Repeat(char),
+    // Figurative constants written without code page translation
+    Raw(u8),
+}
+
+impl RecordEncoder {
+    pub fn new(layout: RecordLayout) -> RecordEncoder {
+        RecordEncoder::with_options(layout, FormatOptions::default())
+    }
+
+    pub fn with_options(layout: RecordLayout, options: FormatOptions) -> RecordEncoder {
+        RecordEncoder { layout, options }
+    }
+
+    pub fn layout(&self) -> &RecordLayout {
+        &self.layout
+    }
+
+    /// Encodes a record of raw bytes, writing zoned fields with `options.byte_encoding` and
+    /// alphanumeric fields with `options.code_page`.
+    pub fn encode_bytes(&self, record: &Record) -> Result<Vec<u8>, Error> {
+        self.writer().encode_bytes(record)
+    }
+
+    /// Encodes a text record, writing zoned fields with `options.encoding`. Offsets count
+    /// characters rather than bytes.
+    pub fn encode_str(&self, record: &Record) -> Result<String, Error> {
+        self.writer().encode_str(record)
+    }
+
+    fn writer(&self) -> Writer<'_> {
+        Writer {
+            layout: &self.layout,
+            options: &self.options,
+        }
+    }
+}
+
+struct Writer<'a> {
+    layout: &'a RecordLayout,
+    options: &'a FormatOptions,
+}
+
+impl Writer<'_> {
+    fn encode_bytes(&self, record: &Record) -> Result<Vec<u8>, Error> {
+        let code_page = self.options.code_page;
+        let space = code_page.encode_char(' ')?;
+        let mut buf = vec![space; self.layout.record_length()];
+        self.encode(record, |field, content| {
+            let out = &mut buf[field.offset..field.offset + field.length()];
+            match content {
+                Content::Number(value) => self.encode_bytes_number(out, field, value),
+                Content::Text(text) => {
+                    out.fill(space);
+                    for (slot, c) in out.iter_mut().zip(text.chars()) {
+                        *slot = code_page.encode_char(c)?;
+                    }
+                    Ok(())
+                }
+                Content::Repeat(c) => {
+                    let byte = code_page.encode_char(c)?;
+                    out.fill(byte);
+                    Ok(())
+                }
+                Content::Raw(byte) => {
+                    out.fill(byte);
+                    Ok(())
+                }
+            }
+        })?;
+        Ok(buf)
+    }
+
+    fn encode_str(&self, record: &Record) -> Result<String, Error> {
+        let mut chars = vec![' '; self.layout.record_length()];
+        self.encode(record, |field, content| {
+            let out = &mut chars[field.offset..field.offset + field.length()];
+            match content {
+                Content::Number(value) => {
+                    let text = core::format_with_picture(
+                        value,
+                        &field.picture,
+                        self.options.encoding,
+                        &self.field_options(field),
+                    )?;
+                    for (slot, c) in out.iter_mut().zip(text.chars()) {
+                        *slot = c;
+                    }
+                }
+                Content::Text(text) => {
+                    out.fill(' ');
+                    for (slot, c) in out.iter_mut().zip(text.chars()) {
+                        *slot = c;
+                    }
+                }
+                Content::Repeat(c) => out.fill(c),
+                Content::Raw(byte) => out.fill(char::from(byte)),
+            }
+            Ok(())
+        })?;
+        Ok(chars.into_iter().collect())
+    }
+
+    fn encode<F>(&self, record: &Record, mut write: F) -> Result<(), Error>
+    where
+        F: FnMut(&Field, Content) -> Result<(), Error>,
+    {
+        for field in self.layout.fields() {
+            let supplied = match field.is_filler() {
+                true => None,
+                false => record.get(&field.name),
+            };
+            if field.redefines && supplied.is_none() {
+                continue;
+            }
+            let content = match supplied {
+                Some(value) => self.content(field, value),
+                None => Ok(initial_content(field)),
+            };
+            content
+                .and_then(|content| write(field, content))
+                .map_err(|source| Error::FieldError {
+                    field: field.name.clone(),
+                    offset: field.offset,
+                    source: Box::new(source),
+                })?;
+        }
+        Ok(())
+    }
+
+    fn content<'a>(&self, field: &Field, value: &'a FieldValue) -> Result<Content<'a>, Error> {
+        let picture = &field.picture;
+        match value {
+            FieldValue::Number(_) if !picture.is_numeric() => {
+                Err(Error::NonNumericPicture(picture.category()))
+            }
+            FieldValue::Number(number) => Ok(Content::Number(*number)),
+            FieldValue::Text(_) if picture.is_numeric() => Err(Error::ExpectedNumber),
+            FieldValue::Text(text) => {
+                let required = text.chars().count();
+                let available = field.length();
+                if required > available && self.options.overflow == OverflowPolicy::Reject {
+                    return Err(Error::FieldTooNarrow {
+                        required,
+                        available,
+                    });
+                }
+                Ok(Content::Text(text))
+            }
+        }
+    }
+
+    fn encode_bytes_number(
+        &self,
+        out: &mut [u8],
+        field: &Field,
+        value: Decimal,
+    ) -> Result<(), Error> {
+        let picture = &field.picture;
+        let bytes = match picture.usage() {
+            Usage::Display => {
+                return core::format_into_with_picture(
+                    out,
+                    value,
+                    picture,
+                    self.options.byte_encoding,
+                    &self.field_options(field),
+                )
+            }
+            Usage::PackedDecimal => packed::pack_with_picture(value, picture, self.options)?,
+            Usage::Binary | Usage::NativeBinary => {
+                binary::write_with_picture(value, picture, self.options)?
+            }
+        };
+        out.copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    // The sign position comes from the field's own SIGN clause
+    fn field_options(&self, field: &Field) -> FormatOptions {
+        FormatOptions {
+            sign_position: field.sign_position,
+            ..*self.options
+        }
+    }
+}
+
+fn initial_content(field: &Field) -> Content<'_> {
+    let numeric = field.picture.is_numeric();
+    match &field.value {
+        Some(Value::Numeric(number)) if numeric => Content::Number(*number),
+        Some(Value::Alphanumeric(text)) => Content::Text(text),
+        Some(Value::Spaces) => Content::Repeat(' '),
+        Some(Value::Zeros) if numeric => Content::Number(Decimal::ZERO),
+        Some(Value::Zeros) => Content::Repeat('0'),
+        Some(Value::LowValues) => Content::Raw(0x00),
+        Some(Value::HighValues) => Content::Raw(0xFF),
+        _ if numeric && !field.is_filler() => Content::Number(Decimal::ZERO),
+        _ => Content::Repeat(' '),
+    }
+}
+
+fn check_length(layout: &RecordLayout, actual: usize) -> Result<(), Error> {
+    let required = layout.record_length();
+    if actual < required {
+        return Err(Error::RecordTooShort { required, actual });
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::encoding::{Ascii, Ebcdic, Encoding, IbmI, MicroFocus, Ncr, Unisys};
+use crate::error::Error;
+use std::sync::{PoisonError, RwLock};
+
+/// The built-in dialects, in the order ties are broken when detecting.
+pub const BUILTIN: [(&str, &dyn Encoding); 6] = [
+    ("ebcdic", &Ebcdic),
+    ("ascii", &Ascii),
+    ("micro-focus", &MicroFocus),
+    ("ibm-i", &IbmI),
+    ("unisys", &Unisys),
+    ("ncr", &Ncr),
+];
+
+static CUSTOM: RwLock<Vec<(&'static str, &'static dyn Encoding)>> = RwLock::new(Vec::new());
+
+// Names match ignoring case, hyphens, underscores and spaces, so "microfocus", "Micro Focus"
+// and "micro-focus" are the same dialect
+fn same_name(a: &str, b: &str) -> bool {
+    let key = |name: &str| {
+        name.chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    key(a) == key(b)
+}
+
+/// Adds an encoding under `name`, usually once at startup.
+pub fn register(name: &'static str, encoding: &'static dyn Encoding) -> Result<(), Error> {
+    let mut custom = CUSTOM.write().unwrap_or_else(PoisonError::into_inner);
+    let taken = BUILTIN
+        .iter()
+        .chain(custom.iter())
+        .any(|&(existing, _)| same_name(existing, name));
+    if taken {
+        return Err(Error::DuplicateEncoding(name.to_string()));
+    }
+    custom.push((name, encoding));
+    Ok(())
+}
+
+/// Finds a built-in or registered encoding by name.
+pub fn lookup(name: &str) -> Result<&'static dyn Encoding, Error> {
+    entries()
+        .into_iter()
+        .find(|&(existing, _)| same_name(existing, name))
+        .map(|(_, encoding)| encoding)
+        .ok_or_else(|| Error::UnknownEncoding(name.to_string()))
+}
+
+/// Every available encoding, built-in ones first, then in the order they were registered.
+pub fn entries() -> Vec<(&'static str, &'static dyn Encoding)> {
+    let custom = CUSTOM.read().unwrap_or_else(PoisonError::into_inner);
+    BUILTIN.iter().chain(custom.iter()).copied().collect()
+}
+
+/// The names of every available encoding, in the order of [`entries`].
+pub fn names() -> Vec<&'static str> {
+    entries().into_iter().map(|(name, _)| name).collect()
+}
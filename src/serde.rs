@@ -0,0 +1,120 @@
+use crate::core;
+use crate::encoding::Ebcdic;
+use crate::error::Error;
+use crate::options::{FormatOptions, ParseOptions};
+use ::serde::de::{self, Visitor};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use rust_decimal::Decimal;
+use std::fmt;
+
+/// A `Decimal` that serializes as a `S9(INT)V9(FRAC)` overpunched string.
+///
+/// Serializing pads to exactly `INT + FRAC` digits and fails when the value needs more;
+/// deserializing accepts any number of digits, like `extract`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Overpunch<const INT: usize, const FRAC: usize>(pub Decimal);
+
+impl<const INT: usize, const FRAC: usize> From<Decimal> for Overpunch<INT, FRAC> {
+    fn from(value: Decimal) -> Self {
+        Overpunch(value)
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> From<Overpunch<INT, FRAC>> for Decimal {
+    fn from(value: Overpunch<INT, FRAC>) -> Self {
+        value.0
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> Serialize for Overpunch<INT, FRAC> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let formatted = format_fixed(self.0, INT, FRAC).map_err(::serde::ser::Error::custom)?;
+        serializer.serialize_str(&formatted)
+    }
+}
+
+impl<'de, const INT: usize, const FRAC: usize> Deserialize<'de> for Overpunch<INT, FRAC> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_decimal(deserializer, FRAC).map(Overpunch)
+    }
+}
+
+fn format_fixed(value: Decimal, integer_digits: usize, decimals: usize) -> Result<String, Error> {
+    let options = FormatOptions::default();
+    let (digits, sign) = core::decimal_digits(value, decimals, &options)?;
+    let digits = core::fit_width(digits, integer_digits + decimals, options.overflow)?;
+    core::apply_sign(digits, sign, &Ebcdic, options.sign_position)
+}
+
+fn serialize_decimal<S: Serializer>(
+    value: &Decimal,
+    decimals: usize,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let formatted =
+        core::format_with_encoding(*value, decimals, &Ebcdic, &FormatOptions::default())
+            .map_err(::serde::ser::Error::custom)?;
+    serializer.serialize_str(&formatted)
+}
+
+fn deserialize_decimal<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    decimals: usize,
+) -> Result<Decimal, D::Error> {
+    deserializer.deserialize_str(OverpunchVisitor { decimals })
+}
+
+struct OverpunchVisitor {
+    decimals: usize,
+}
+
+impl Visitor<'_> for OverpunchVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an overpunched numeric string")
+    }
+
+    fn visit_str<E: de::Error>(self, raw: &str) -> Result<Decimal, E> {
+        core::extract_with_encoding(raw, self.decimals, &Ebcdic, &ParseOptions::default())
+            .map_err(E::custom)
+    }
+}
+
+macro_rules! with_decimals {
+    ($($name:ident => $decimals:literal),* $(,)?) => {
+        $(
+            #[doc = concat!(
+                "`#[serde(with = \"overpunch_ng::serde::", stringify!($name), "\")]` for a ",
+                "`Decimal` stored with ", stringify!($decimals), " implied decimal places."
+            )]
+            pub mod $name {
+                use rust_decimal::Decimal;
+                use ::serde::{Deserializer, Serializer};
+
+                pub fn serialize<S: Serializer>(
+                    value: &Decimal,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    super::serialize_decimal(value, $decimals, serializer)
+                }
+
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Decimal, D::Error> {
+                    super::deserialize_decimal(deserializer, $decimals)
+                }
+            }
+        )*
+    };
+}
+
+with_decimals! {
+    s9v0 => 0,
+    s9v1 => 1,
+    s9v2 => 2,
+    s9v3 => 3,
+    s9v4 => 4,
+    s9v5 => 5,
+    s9v6 => 6,
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "bigint")]
+#![allow(clippy::disallowed_methods)]
+
+use num_bigint::BigInt;
+use overpunch_ng::bigint::{extract_big, format_big, ScaledBigInt};
+use overpunch_ng::error::Error;
+use std::str::FromStr;
+
+fn big(s: &str) -> BigInt {
+    BigInt::from_str(s).unwrap()
+}
+
+#[test]
+fn test_extract_beyond_decimal_range() {
+    let value = extract_big("123456789012345678901234567890J", 2).unwrap();
+    assert_eq!(value.mantissa, big("-1234567890123456789012345678901"));
+    assert_eq!(value.scale, 2);
+
+    let value = extract_big("0000{", 0).unwrap();
+    assert_eq!(value.mantissa, big("0"));
+}
+
+#[test]
+fn test_format_beyond_decimal_range() {
+    let value = ScaledBigInt {
+        mantissa: big("9999999999999999999999999999999"),
+        scale: 4,
+    };
+    assert_eq!(
+        format_big(&value).unwrap(),
+        "999999999999999999999999999999I"
+    );
+
+    let value = ScaledBigInt {
+        mantissa: big("-5"),
+        scale: 2,
+    };
+    assert_eq!(format_big(&value).unwrap(), "00N");
+}
+
+#[test]
+fn test_big_roundtrip() {
+    for raw in ["1", "-42", "100000000000000000000000000000000000000"] {
+        let value = ScaledBigInt {
+            mantissa: big(raw),
+            scale: 3,
+        };
+        let formatted = format_big(&value).unwrap();
+        assert_eq!(extract_big(&formatted, 3).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_extract_big_errors() {
+    assert_eq!(extract_big("", 0), Err(Error::EmptyField));
+    assert_eq!(
+        extract_big("12X4", 0),
+        Err(Error::ParseError {
+            invalid_char: 'X',
+            index: 2
+        })
+    );
+}
@@ -0,0 +1,200 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::binary::{read, read_with_picture, write_into, write_with_picture};
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    BinaryOptions, ByteOrder, FormatOptions, OverflowPolicy, ParseOptions, Picture, Truncation,
+    Usage,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn pic(s: &str) -> Picture {
+    Picture::parse(s).unwrap()
+}
+
+fn parse_options(truncation: Truncation, byte_order: ByteOrder) -> ParseOptions {
+    ParseOptions {
+        binary: BinaryOptions {
+            truncation,
+            byte_order,
+        },
+        ..ParseOptions::default()
+    }
+}
+
+fn format_options(truncation: Truncation, byte_order: ByteOrder) -> FormatOptions {
+    FormatOptions {
+        binary: BinaryOptions {
+            truncation,
+            byte_order,
+        },
+        ..FormatOptions::default()
+    }
+}
+
+#[test]
+fn test_picture_widths() {
+    assert_eq!(pic("S9(4) COMP").width(), 2);
+    assert_eq!(pic("S9(9) COMP-4").width(), 4);
+    assert_eq!(pic("S9(18) BINARY").width(), 8);
+}
+
+#[test]
+fn test_read() {
+    let options = ParseOptions::default();
+    assert_eq!(
+        read(&[0x30, 0x39], 2, true, &options).unwrap(),
+        dec("123.45")
+    );
+    assert_eq!(
+        read(&[0xCF, 0xC7], 2, true, &options).unwrap(),
+        dec("-123.45")
+    );
+    assert_eq!(
+        read(&[0xCF, 0xC7], 0, false, &options).unwrap(),
+        dec("53191")
+    );
+    assert_eq!(
+        read(
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            0,
+            true,
+            &options
+        )
+        .unwrap(),
+        dec("-1")
+    );
+    assert_eq!(read(&[0xFF; 16], 0, true, &options).unwrap(), dec("-1"));
+    assert!(matches!(
+        read(&[0xFF; 16], 0, false, &options),
+        Err(Error::OverflowError(_))
+    ));
+    let mut top_bit = [0u8; 16];
+    top_bit[0] = 0x80;
+    assert!(matches!(
+        read(&top_bit, 0, false, &options),
+        Err(Error::OverflowError(_))
+    ));
+
+    let little = parse_options(Truncation::Standard, ByteOrder::LittleEndian);
+    assert_eq!(
+        read(&[0x39, 0x30], 2, true, &little).unwrap(),
+        dec("123.45")
+    );
+
+    assert_eq!(read(&[], 0, true, &options), Err(Error::EmptyField));
+}
+
+#[test]
+fn test_write_into() {
+    let options = FormatOptions::default();
+    let mut buf = [0u8; 4];
+    write_into(&mut buf, dec("-123.45"), 2, true, &options).unwrap();
+    assert_eq!(buf, [0xFF, 0xFF, 0xCF, 0xC7]);
+
+    let little = format_options(Truncation::Standard, ByteOrder::LittleEndian);
+    write_into(&mut buf, dec("123.45"), 2, true, &little).unwrap();
+    assert_eq!(buf, [0x39, 0x30, 0x00, 0x00]);
+
+    let mut half = [0u8; 2];
+    assert!(matches!(
+        write_into(&mut half, dec("32768"), 0, true, &options),
+        Err(Error::OverflowError(_))
+    ));
+    write_into(&mut half, dec("65535"), 0, false, &options).unwrap();
+    assert_eq!(half, [0xFF, 0xFF]);
+    assert_eq!(
+        write_into(&mut half, dec("-1"), 0, false, &options),
+        Err(Error::UnexpectedSign)
+    );
+
+    let truncate = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    write_into(&mut half, dec("65537"), 0, false, &truncate).unwrap();
+    assert_eq!(half, [0x00, 0x01]);
+}
+
+#[test]
+fn test_trunc_std_limits_to_picture_digits() {
+    let picture = pic("S9(4) COMP");
+    let options = FormatOptions::default();
+    assert_eq!(
+        write_with_picture(dec("-9999"), &picture, &options).unwrap(),
+        vec![0xD8, 0xF1]
+    );
+    assert_eq!(
+        write_with_picture(dec("12345"), &picture, &options),
+        Err(Error::FieldTooNarrow {
+            required: 5,
+            available: 4
+        })
+    );
+
+    let truncate = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        write_with_picture(dec("12345"), &picture, &truncate).unwrap(),
+        vec![0x09, 0x29]
+    );
+
+    assert!(matches!(
+        read_with_picture(&[0x30, 0x39], &picture, &ParseOptions::default()),
+        Err(Error::OverflowError(_))
+    ));
+}
+
+#[test]
+fn test_trunc_bin_uses_full_binary_range() {
+    let picture = pic("S9(4) COMP");
+    let options = format_options(Truncation::Binary, ByteOrder::BigEndian);
+    assert_eq!(
+        write_with_picture(dec("12345"), &picture, &options).unwrap(),
+        vec![0x30, 0x39]
+    );
+    assert!(matches!(
+        write_with_picture(dec("40000"), &picture, &options),
+        Err(Error::OverflowError(_))
+    ));
+
+    let parse = parse_options(Truncation::Binary, ByteOrder::BigEndian);
+    assert_eq!(
+        read_with_picture(&[0x30, 0x39], &picture, &parse).unwrap(),
+        dec("12345")
+    );
+
+    // COMP-5 ignores TRUNC(STD)
+    let picture = pic("S9(4) COMP-5");
+    assert_eq!(
+        write_with_picture(dec("12345"), &picture, &FormatOptions::default()).unwrap(),
+        vec![0x30, 0x39]
+    );
+    assert_eq!(
+        read_with_picture(&[0x30, 0x39], &picture, &ParseOptions::default()).unwrap(),
+        dec("12345")
+    );
+}
+
+#[test]
+fn test_picture_scale_is_implied() {
+    let picture = pic("S9(7)V99 COMP");
+    let bytes = write_with_picture(dec("-1234.5"), &picture, &FormatOptions::default()).unwrap();
+    assert_eq!(bytes, vec![0xFF, 0xFE, 0x1D, 0xC6]);
+    assert_eq!(
+        read_with_picture(&bytes, &picture, &ParseOptions::default()).unwrap(),
+        dec("-1234.50")
+    );
+
+    assert_eq!(
+        read_with_picture(&[0x00, 0x01], &pic("S9(4)"), &ParseOptions::default()),
+        Err(Error::UnsupportedUsage(Usage::Display))
+    );
+}
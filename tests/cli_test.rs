@@ -0,0 +1,178 @@
+#![cfg(feature = "cli")]
+#![allow(clippy::disallowed_methods)]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+const FIELDS: &str = "NAME:X(5),AMOUNT:S9(3)V99,QTY:S9(6)";
+const TEXT: &str = "ALICE1234N00012{\nBOB  0050}00000J\n";
+
+fn overpunch(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_overpunch"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("overpunch-cli-{}-{name}", std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_decode_and_encode_csv() {
+    let csv = stdout(&overpunch(&["decode", "-f", FIELDS], TEXT.as_bytes()));
+    assert_eq!(csv, "NAME,AMOUNT,QTY\nALICE,-123.45,120\nBOB,-5.00,-1\n");
+
+    let text = stdout(&overpunch(&["encode", "-f", FIELDS], csv.as_bytes()));
+    assert_eq!(text, TEXT);
+
+    // Empty cells are initialised, text is padded
+    let text = stdout(&overpunch(
+        &["encode", "-f", FIELDS],
+        b"QTY,NAME\n7,EVE\n,\n",
+    ));
+    assert_eq!(text, "EVE  0000{00000G\n     0000{00000{\n");
+}
+
+#[test]
+fn test_decode_and_encode_json_lines() {
+    let jsonl = stdout(&overpunch(
+        &["decode", "-f", FIELDS, "--to", "jsonl"],
+        TEXT.as_bytes(),
+    ));
+    assert_eq!(
+        jsonl,
+        "{\"NAME\":\"ALICE\",\"AMOUNT\":-123.45,\"QTY\":120}\n\
+         {\"NAME\":\"BOB\",\"AMOUNT\":-5.00,\"QTY\":-1}\n"
+    );
+
+    let text = stdout(&overpunch(
+        &["encode", "-f", FIELDS, "--from", "jsonl"],
+        jsonl.as_bytes(),
+    ));
+    assert_eq!(text, TEXT);
+
+    // Numbers may also arrive as strings; every digit survives
+    let text = stdout(&overpunch(
+        &["encode", "-f", "BIG:S9(20)", "--from", "jsonl"],
+        b"{\"big\":\"-12345678901234567890\"}\n{\"BIG\":12345678901234567890}\n",
+    ));
+    assert_eq!(text, "1234567890123456789}\n1234567890123456789{\n");
+}
+
+#[test]
+fn test_ebcdic_datasets() {
+    let csv = "NAME,AMOUNT,QTY\nALICE,-123.45,120\n";
+    let fixed = overpunch(&["encode", "-f", FIELDS, "--ebcdic"], csv.as_bytes());
+    assert!(fixed.status.success());
+    assert_eq!(
+        fixed.stdout,
+        [
+            0xC1, 0xD3, 0xC9, 0xC3, 0xC5, 0xF1, 0xF2, 0xF3, 0xF4, 0xD5, 0xF0, 0xF0, 0xF0, 0xF1,
+            0xF2, 0xC0
+        ]
+    );
+    let decoded = stdout(&overpunch(
+        &["decode", "-f", FIELDS, "--ebcdic"],
+        &fixed.stdout,
+    ));
+    assert_eq!(decoded, csv);
+
+    let blocked = overpunch(
+        &["encode", "-f", FIELDS, "--ebcdic", "--recfm", "vb"],
+        csv.as_bytes(),
+    );
+    assert_eq!(blocked.stdout[..8], [0, 24, 0, 0, 0, 20, 0, 0]);
+    assert_eq!(blocked.stdout[8..], fixed.stdout[..]);
+    let decoded = stdout(&overpunch(
+        &["decode", "-f", FIELDS, "--ebcdic", "--recfm", "vb"],
+        &blocked.stdout,
+    ));
+    assert_eq!(decoded, csv);
+}
+
+#[test]
+fn test_copybook_and_inspect() {
+    let copybook = temp_file(
+        "inspect.cpy",
+        "01 ITEM.\n   05 SKU PIC X(4).\n   05 FILLER PIC X.\n   05 PRICE PIC S9(3)V99.\n",
+    );
+    let copybook = copybook.to_str().unwrap();
+    let input = temp_file("inspect.txt", "AB12 1234E\nCD34 99x9{\n");
+
+    let output = stdout(&overpunch(
+        &[
+            "inspect",
+            "-c",
+            copybook,
+            "-n",
+            "2",
+            input.to_str().unwrap(),
+        ],
+        b"",
+    ));
+    let lines: Vec<&str> = output.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        [
+            "FIELD   OFFSET  LENGTH  PICTURE",
+            "SKU          0       4  X(4)",
+            "FILLER       4       1  X(1)",
+            "PRICE        5       5  S9(3)V9(2)",
+            "record length 10",
+            "",
+            "record 1",
+            "  SKU                     AB12  \"AB12\"",
+            "  PRICE                  1234E  123.45",
+            "",
+            "record 2",
+            "  SKU                     CD34  \"CD34\"",
+            "  PRICE                  99x9{  error: field PRICE at offset 5: parse error: invalid character 'x' at index 2",
+        ]
+    );
+    fs::remove_file(copybook).unwrap();
+    fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn test_errors_name_the_record() {
+    let output = overpunch(&["decode", "-f", FIELDS], b"ALICE1234N00012{\nBOB  12\n");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "overpunch: record 2: record is 7 bytes long but the layout needs 16\n"
+    );
+
+    let output = overpunch(&["encode", "-f", FIELDS], b"NAME,PRICE\nA,1\n");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "overpunch: column `PRICE` is not in the layout\n"
+    );
+
+    let output = overpunch(&["encode", "-f", FIELDS], b"NAME,QTY\nA,lots\n");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "overpunch: record 1: `QTY`: `lots` is not a number\n"
+    );
+
+    let output = overpunch(&["decode", "-f", "AMOUNT:S9(3)W"], b"");
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("overpunch: field `AMOUNT`: "));
+}
@@ -0,0 +1,237 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::copybook::{Field, RecordLayout, Value};
+use overpunch_ng::error::Error;
+use overpunch_ng::{Picture, SignPosition, Usage};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn positions(layout: &RecordLayout) -> Vec<(&str, usize, usize)> {
+    layout
+        .fields()
+        .iter()
+        .map(|field| (field.name.as_str(), field.offset, field.length()))
+        .collect()
+}
+
+fn line_of(result: Result<RecordLayout, Error>) -> usize {
+    match result {
+        Err(Error::InvalidCopybook { line, .. }) => line,
+        other => panic!("expected InvalidCopybook, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_offsets_and_lengths() {
+    let layout = RecordLayout::parse(
+        "
+       01  CUSTOMER-RECORD.
+           05  CUSTOMER-ID        PIC 9(6).
+           05  CUSTOMER-NAME      PIC X(20).
+           05  BALANCE            PIC S9(7)V99 COMP-3.
+           05  CREDIT-LIMIT       PIC S9(5)V99.
+           05  ORDER-COUNT        PIC S9(4) USAGE IS BINARY.
+           05  FILLER             PIC X(3).
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        positions(&layout),
+        vec![
+            ("CUSTOMER-ID", 0, 6),
+            ("CUSTOMER-NAME", 6, 20),
+            ("BALANCE", 26, 5),
+            ("CREDIT-LIMIT", 31, 7),
+            ("ORDER-COUNT", 38, 2),
+            ("FILLER", 40, 3),
+        ]
+    );
+    assert_eq!(layout.record_length(), 43);
+
+    let balance = layout.field("balance").unwrap();
+    assert_eq!(balance.picture.usage(), Usage::PackedDecimal);
+    assert_eq!(balance.picture.scale(), 2);
+    assert!(layout.fields()[5].is_filler());
+}
+
+#[test]
+fn test_occurs_expands_every_occurrence() {
+    let layout = RecordLayout::parse(
+        "
+       01  ORDER.
+           05  LINE-COUNT         PIC 99.
+           05  ORDER-LINE OCCURS 2 TIMES.
+               10  SKU            PIC X(4).
+               10  QTY            PIC 9(3) OCCURS 2.
+           05  TRAILER            PIC X.
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        positions(&layout),
+        vec![
+            ("LINE-COUNT", 0, 2),
+            ("SKU(1)", 2, 4),
+            ("QTY(1,1)", 6, 3),
+            ("QTY(1,2)", 9, 3),
+            ("SKU(2)", 12, 4),
+            ("QTY(2,1)", 16, 3),
+            ("QTY(2,2)", 19, 3),
+            ("TRAILER", 22, 1),
+        ]
+    );
+
+    let layout = RecordLayout::parse(
+        "01 T. 05 N PIC 9. 05 ITEM PIC X(2) OCCURS 1 TO 5 TIMES DEPENDING ON N.",
+    )
+    .unwrap();
+    assert_eq!(layout.record_length(), 11);
+}
+
+#[test]
+fn test_redefines_overlays_storage() {
+    let layout = RecordLayout::parse(
+        "
+       01  PAYMENT.
+           05  METHOD             PIC X.
+           05  CARD-DATA.
+               10  CARD-NUMBER    PIC 9(16).
+           05  BANK-DATA REDEFINES CARD-DATA.
+               10  SORT-CODE      PIC 9(6).
+               10  ACCOUNT        PIC 9(8).
+           05  AMOUNT             PIC S9(5)V99 COMP-3.
+",
+    )
+    .unwrap();
+
+    assert_eq!(
+        positions(&layout),
+        vec![
+            ("METHOD", 0, 1),
+            ("CARD-NUMBER", 1, 16),
+            ("SORT-CODE", 1, 6),
+            ("ACCOUNT", 7, 8),
+            ("AMOUNT", 17, 4),
+        ]
+    );
+    assert!(!layout.field("CARD-NUMBER").unwrap().redefines);
+    assert!(layout.field("ACCOUNT").unwrap().redefines);
+    assert_eq!(layout.record_length(), 21);
+
+    assert_eq!(
+        line_of(RecordLayout::parse(
+            "01 R.\n 05 A PIC X.\n 05 B REDEFINES MISSING PIC X."
+        )),
+        3
+    );
+}
+
+#[test]
+fn test_values_sign_and_inherited_usage() {
+    let layout = RecordLayout::parse(
+        "
+       01  HEADER.
+           05  RECORD-TYPE        PIC X(2) VALUE 'H1'.
+           05  FILLER             PIC X(3) VALUE SPACES.
+           05  VERSION            PIC 9(2) VALUE 7.
+           05  RATE               PIC S9V99 VALUE -1.25
+                                  SIGN IS LEADING SEPARATE CHARACTER.
+           05  TOTALS COMP-3.
+               10  DEBITS         PIC S9(5).
+               10  CREDITS        PIC S9(5).
+           88  IGNORED            VALUE 'X'.
+",
+    )
+    .unwrap();
+
+    let value = |name: &str| layout.field(name).unwrap().value.clone();
+    assert_eq!(value("RECORD-TYPE"), Some(Value::Alphanumeric("H1".into())));
+    assert_eq!(layout.fields()[1].value, Some(Value::Spaces));
+    assert_eq!(value("VERSION"), Some(Value::Numeric(dec("7"))));
+    assert_eq!(value("RATE"), Some(Value::Numeric(dec("-1.25"))));
+
+    let rate = layout.field("RATE").unwrap();
+    assert_eq!(rate.sign_position, SignPosition::LeadingSeparate);
+    assert_eq!((rate.offset, rate.length()), (7, 4));
+
+    assert_eq!(
+        positions(&layout)[4..].to_vec(),
+        vec![("DEBITS", 11, 3), ("CREDITS", 14, 3)]
+    );
+    assert_eq!(
+        layout.field("DEBITS").unwrap().picture.usage(),
+        Usage::PackedDecimal
+    );
+}
+
+#[test]
+fn test_fixed_format_source() {
+    let copybook = "\
+000100* CUSTOMER MASTER RECORD                                          CUSTMAST
+000200 01  CUST-REC.                                                    CUSTMAST
+000300     05  CUST-NAME          PIC X(10).                            CUSTMAST
+000400     05  CUST-NOTE          PIC X(30) VALUE 'A LONG NOTE THAT IS CCUSTMAST
+000500-    'ONTINUED'.                                                  CUSTMAST
+000600     05  CUST-CODE          PIC X(2).      *> trailing comment
+";
+    let layout = RecordLayout::parse(copybook).unwrap();
+    assert_eq!(
+        positions(&layout),
+        vec![
+            ("CUST-NAME", 0, 10),
+            ("CUST-NOTE", 10, 30),
+            ("CUST-CODE", 40, 2)
+        ]
+    );
+    assert_eq!(
+        layout.field("CUST-NOTE").unwrap().value,
+        Some(Value::Alphanumeric("A LONG NOTE THAT IS CONTINUED".into()))
+    );
+}
+
+#[test]
+fn test_multiple_records_share_storage() {
+    let layout =
+        RecordLayout::parse("01 SHORT-REC PIC X(10).\n01 LONG-REC.\n 05 BODY PIC X(25).").unwrap();
+    assert_eq!(
+        positions(&layout),
+        vec![("SHORT-REC", 0, 10), ("BODY", 0, 25)]
+    );
+    assert_eq!(layout.record_length(), 25);
+}
+
+#[test]
+fn test_invalid_copybooks_report_line() {
+    assert_eq!(line_of(RecordLayout::parse("01 R.\n 05 A PIC 9(3)Q.")), 2);
+    assert_eq!(line_of(RecordLayout::parse("01 R.\n\n 05 A.")), 3);
+    assert_eq!(line_of(RecordLayout::parse("01 R.\n 05 A COMP-1.")), 2);
+    assert_eq!(line_of(RecordLayout::parse("01 R PIC X VALUE 'OPEN.")), 1);
+    assert_eq!(line_of(RecordLayout::parse("R PIC X.")), 1);
+    assert_eq!(
+        line_of(RecordLayout::parse("01 R.\n 05 A PIC 9 SIGN LEADING.")),
+        2
+    );
+    assert_eq!(
+        line_of(RecordLayout::parse("01 R.\n 05 A PIC X(2) COMP.")),
+        2
+    );
+    assert_eq!(line_of(RecordLayout::parse("      * only a comment")), 1);
+}
+
+#[test]
+fn test_hand_built_layout() {
+    let mut amount = Field::new("AMOUNT", 4, Picture::parse("S9(5)V99").unwrap());
+    amount.sign_position = SignPosition::TrailingSeparate;
+    let layout = RecordLayout::new(vec![
+        Field::new("ID", 0, Picture::parse("X(4)").unwrap()),
+        amount,
+    ]);
+    assert_eq!(layout.record_length(), 12);
+    assert_eq!(layout.field("amount").unwrap().length(), 8);
+}
@@ -0,0 +1,203 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::dataset::{RecordFormat, RecordReader};
+use overpunch_ng::error::Error;
+use std::io::{self, Read};
+
+fn read_all<R: Read>(reader: &mut RecordReader<R>) -> Result<Vec<Vec<u8>>, Error> {
+    let mut records = Vec::new();
+    while let Some(record) = reader.next_record()? {
+        records.push(record.to_vec());
+    }
+    Ok(records)
+}
+
+fn rdw(data: &[u8]) -> Vec<u8> {
+    let length = (data.len() + 4) as u16;
+    let mut bytes = length.to_be_bytes().to_vec();
+    bytes.extend_from_slice(&[0, 0]);
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+fn block(records: &[&[u8]]) -> Vec<u8> {
+    let body: Vec<u8> = records.iter().flat_map(|r| rdw(r)).collect();
+    let mut bytes = ((body.len() + 4) as u16).to_be_bytes().to_vec();
+    bytes.extend_from_slice(&[0, 0]);
+    bytes.extend(body);
+    bytes
+}
+
+// Hands out one byte per call to exercise short reads
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((byte, rest)), Some(slot)) => {
+                *slot = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_fixed_records() {
+    let mut reader = RecordReader::new(&b"00012A00034B"[..], RecordFormat::Fixed(6));
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        vec![b"00012A".to_vec(), b"00034B".to_vec()]
+    );
+    assert_eq!(reader.position(), 12);
+    assert_eq!(reader.next_record(), Ok(None));
+
+    let mut reader = RecordReader::new(&b"00012A000"[..], RecordFormat::Fixed(6));
+    assert_eq!(reader.next_record(), Ok(Some(&b"00012A"[..])));
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::TruncatedRecord {
+            offset: 6,
+            required: 6,
+            available: 3
+        })
+    );
+
+    let mut reader = RecordReader::new(&b""[..], RecordFormat::Fixed(6));
+    assert_eq!(reader.next_record(), Ok(None));
+
+    let mut reader = RecordReader::new(&b"123"[..], RecordFormat::Fixed(0));
+    assert!(matches!(
+        reader.next_record(),
+        Err(Error::InvalidDescriptor { offset: 0, .. })
+    ));
+}
+
+#[test]
+fn test_variable_records() {
+    let mut input = rdw(b"ABC");
+    input.extend(rdw(b""));
+    input.extend(rdw(b"12345"));
+    let mut reader = RecordReader::new(&input[..], RecordFormat::Variable);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        vec![b"ABC".to_vec(), Vec::new(), b"12345".to_vec()]
+    );
+    assert_eq!(reader.position(), input.len() as u64);
+
+    // Truncated descriptor and truncated data
+    let mut reader = RecordReader::new(&[0u8, 8, 0][..], RecordFormat::Variable);
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::TruncatedRecord {
+            offset: 0,
+            required: 4,
+            available: 3
+        })
+    );
+    let mut reader = RecordReader::new(&[0u8, 8, 0, 0, b'A'][..], RecordFormat::Variable);
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::TruncatedRecord {
+            offset: 4,
+            required: 4,
+            available: 1
+        })
+    );
+
+    // Lengths below four and spanned segments are rejected
+    let mut reader = RecordReader::new(&[0u8, 2, 0, 0][..], RecordFormat::Variable);
+    assert!(matches!(
+        reader.next_record(),
+        Err(Error::InvalidDescriptor { offset: 0, .. })
+    ));
+    let mut reader = RecordReader::new(&[0u8, 5, 1, 0, b'A'][..], RecordFormat::Variable);
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::InvalidDescriptor {
+            offset: 0,
+            reason: "spanned record segments are not supported"
+        })
+    );
+}
+
+#[test]
+fn test_variable_blocked_records() {
+    let mut input = block(&[b"ONE", b"TWO!"]);
+    input.extend(block(&[b"THREE"]));
+    let mut reader = RecordReader::new(&input[..], RecordFormat::VariableBlocked);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        vec![b"ONE".to_vec(), b"TWO!".to_vec(), b"THREE".to_vec()]
+    );
+    assert_eq!(reader.position(), input.len() as u64);
+
+    // Extended BDW with the high bit set and a 31-bit length
+    let body = rdw(b"BIG");
+    let mut input = (0x8000_0000u32 | (body.len() as u32 + 4))
+        .to_be_bytes()
+        .to_vec();
+    input.extend(body);
+    let mut reader = RecordReader::new(&input[..], RecordFormat::VariableBlocked);
+    assert_eq!(read_all(&mut reader).unwrap(), vec![b"BIG".to_vec()]);
+
+    // A record that runs past its block
+    let mut input = vec![0u8, 8, 0, 0];
+    input.extend(rdw(b"TOO LONG"));
+    let mut reader = RecordReader::new(&input[..], RecordFormat::VariableBlocked);
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::InvalidDescriptor {
+            offset: 4,
+            reason: "record extends past the end of its block"
+        })
+    );
+
+    // A block whose records end early
+    let mut reader = RecordReader::new(&[0u8, 20, 0, 0][..], RecordFormat::VariableBlocked);
+    assert_eq!(
+        reader.next_record(),
+        Err(Error::TruncatedRecord {
+            offset: 4,
+            required: 4,
+            available: 0
+        })
+    );
+}
+
+#[test]
+fn test_short_reads() {
+    let input = block(&[b"ABC", b"DEFGH"]);
+    let mut reader = RecordReader::new(Trickle(&input), RecordFormat::VariableBlocked);
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        vec![b"ABC".to_vec(), b"DEFGH".to_vec()]
+    );
+
+    let mut reader = RecordReader::new(Trickle(b"1234567890"), RecordFormat::Fixed(5));
+    assert_eq!(
+        read_all(&mut reader).unwrap(),
+        vec![b"12345".to_vec(), b"67890".to_vec()]
+    );
+}
+
+#[test]
+fn test_io_errors() {
+    struct Failing;
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+        }
+    }
+
+    let mut reader = RecordReader::new(Failing, RecordFormat::Fixed(4));
+    assert!(matches!(
+        reader.next_record(),
+        Err(Error::Io {
+            kind: io::ErrorKind::PermissionDenied,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,75 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::detect::{detect, detect_among, Detection};
+use overpunch_ng::{format_with_dyn_encoding, Ascii, Ebcdic, Encoding, IbmI, Ncr};
+use rust_decimal::Decimal;
+
+fn sample(encoding: &dyn Encoding) -> Vec<String> {
+    [12345, -20, 0, 987, -1, 4500, 33]
+        .into_iter()
+        .map(|v| format_with_dyn_encoding(Decimal::new(v, 2), 2, encoding).unwrap())
+        .collect()
+}
+
+fn ranking(detection: &Detection) -> Vec<&str> {
+    detection.scores.iter().map(|score| score.name).collect()
+}
+
+#[test]
+fn test_detects_each_dialect() {
+    let cases: [(&dyn Encoding, &str); 4] = [
+        (&Ebcdic, "ebcdic"),
+        (&Ascii, "ascii"),
+        (&IbmI, "ibm-i"),
+        (&Ncr, "ncr"),
+    ];
+    for (encoding, name) in cases {
+        let detection = detect(&sample(encoding));
+        let best = detection.best().unwrap();
+        assert_eq!(best.name, name, "{:?}", detection.scores);
+        assert_eq!(best.decoded, 7);
+        assert_eq!(best.canonical, 7);
+        assert_eq!(best.negatives, 2);
+        assert_eq!(best.confidence, 1.0);
+    }
+}
+
+#[test]
+fn test_scores() {
+    let detection = detect(&["1234u", "00012", "  ", "", "1234J"]);
+    assert_eq!(detection.examined, 3);
+    assert_eq!(
+        ranking(&detection),
+        ["micro-focus", "ascii", "ibm-i", "ebcdic", "unisys", "ncr"]
+    );
+
+    let micro_focus = &detection.scores[0];
+    assert_eq!(
+        (
+            micro_focus.decoded,
+            micro_focus.canonical,
+            micro_focus.negatives
+        ),
+        (3, 2, 2)
+    );
+    assert!((micro_focus.confidence - 5.0 / 6.0).abs() < 1e-9);
+
+    let ascii = &detection.scores[1];
+    assert_eq!((ascii.decoded, ascii.canonical), (2, 2));
+    assert!((ascii.confidence - 2.0 / 3.0).abs() < 1e-9);
+
+    // Values that are all negative are less plausible
+    let detection = detect_among(&["12J", "3K"], &[("ebcdic", &Ebcdic)]);
+    assert_eq!(detection.best().unwrap().confidence, 0.75);
+}
+
+#[test]
+fn test_nothing_decodes() {
+    let detection = detect(&["ab#", "z"]);
+    assert!(detection.best().is_none());
+    assert!(detection.scores.iter().all(|score| score.confidence == 0.0));
+
+    let detection = detect::<&str>(&[]);
+    assert_eq!(detection.examined, 0);
+    assert!(detection.best().is_none());
+}
@@ -0,0 +1,103 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    extract_bytes_with_encoding, extract_with_dyn_encoding, extract_with_encoding,
+    format_with_dyn_encoding, format_with_encoding, Ascii, Ebcdic, Encoding, IbmI, MicroFocus, Ncr,
+    ParseOptions, Sign, Unisys,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+const DIALECTS: [&dyn Encoding; 6] = [&Ebcdic, &Ascii, &MicroFocus, &IbmI, &Unisys, &Ncr];
+
+#[test]
+fn test_every_symbol_round_trips() {
+    for encoding in DIALECTS {
+        for sign in [Sign::Positive, Sign::Negative] {
+            for digit in 0..=9 {
+                let c = encoding.encode(digit, sign).unwrap();
+                assert_eq!(encoding.decode(c), Ok((digit, sign)), "{encoding:?} {c}");
+            }
+        }
+        assert_eq!(
+            encoding.encode(10, Sign::Positive),
+            Err(Error::UnsupportedCharacter('\n'))
+        );
+    }
+}
+
+#[test]
+fn test_values_round_trip() {
+    for encoding in DIALECTS {
+        for value in ["-123.45", "123.45", "0.00", "-0.01", "9999.99", "-1000.00"] {
+            let formatted = format_with_dyn_encoding(dec(value), 2, encoding).unwrap();
+            assert_eq!(
+                extract_with_dyn_encoding(&formatted, 2, encoding),
+                Ok(dec(value)),
+                "{encoding:?} {formatted}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_dialect_tables() {
+    let cases: [(&dyn Encoding, &str, &str); 5] = [
+        (&Ascii, "1234u", "12340"),
+        (&MicroFocus, "1234u", "12340"),
+        (&IbmI, "1234N", "12340"),
+        (&Unisys, "1234N", "1234{"),
+        (&Ncr, "1234N", "1234?"),
+    ];
+    for (encoding, negative, positive_zero) in cases {
+        assert_eq!(
+            format_with_dyn_encoding(dec("-123.45"), 2, encoding),
+            Ok(negative.to_string())
+        );
+        assert_eq!(
+            format_with_dyn_encoding(dec("123.40"), 2, encoding),
+            Ok(positive_zero.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_alternative_symbols_are_read() {
+    assert_eq!(
+        extract_with_encoding("12J", 0, &MicroFocus),
+        Ok(dec("-121"))
+    );
+    assert_eq!(extract_with_encoding("12A", 0, &MicroFocus), Ok(dec("121")));
+    assert_eq!(extract_with_encoding("12A", 0, &IbmI), Ok(dec("121")));
+    assert_eq!(extract_with_encoding("12!", 0, &Unisys), Ok(dec("-120")));
+    assert_eq!(extract_with_encoding("12?", 0, &Unisys), Ok(dec("120")));
+    assert_eq!(extract_with_encoding("12}", 0, &Ncr), Ok(dec("-120")));
+
+    // Strict tables reject the other conventions
+    assert_eq!(
+        extract_with_encoding("12J", 0, &Ascii),
+        Err(Error::ParseError {
+            invalid_char: 'J',
+            index: 2
+        })
+    );
+    assert!(extract_with_encoding("12p", 0, &Ebcdic).is_err());
+    assert!(extract_with_encoding("12u", 0, &IbmI).is_err());
+}
+
+#[test]
+fn test_dialects_work_on_bytes() {
+    assert_eq!(
+        extract_bytes_with_encoding(b"1234u", 2, &Ascii, &ParseOptions::default()),
+        Ok(dec("-123.45"))
+    );
+    assert_eq!(
+        format_with_encoding(dec("-7"), 0, &Ascii),
+        Ok("w".to_string())
+    );
+}
@@ -3,8 +3,10 @@
 use overpunch_ng::encoding::{Encoding, Sign};
 use overpunch_ng::error::Error;
 use overpunch_ng::{
-    convert_from_signed_format, convert_to_signed_format, extract, extract_with_encoding, format,
-    format_with_encoding,
+    convert_from_signed_format, convert_from_signed_format_with_options, convert_to_signed_format,
+    convert_to_signed_format_with_options, extract, extract_with_encoding, extract_with_options,
+    format, format_into, format_with_encoding, format_with_options, packed, Category,
+    FormatOptions, OverflowPolicy, ParseOptions, Picture, SignPosition, ZeroSignPolicy,
 };
 use rust_decimal::prelude::Zero;
 use rust_decimal::Decimal;
@@ -14,16 +16,11 @@ fn dec(s: &str) -> Decimal {
     Decimal::from_str(s).unwrap()
 }
 
-// Add a special marker to the environment to indicate we're handling a negative zero
 fn neg_zero() -> Decimal {
-    // Mark this as a negative zero when it reaches our format function
-    std::env::set_var("HANDLE_NEGATIVE_ZERO", "true");
-    Decimal::zero()
+    -Decimal::zero()
 }
 
 fn pos_zero() -> Decimal {
-    // Mark this as a positive zero when it reaches our format function
-    std::env::remove_var("HANDLE_NEGATIVE_ZERO");
     Decimal::zero()
 }
 
@@ -77,7 +74,7 @@ fn test_format_basic_positive() {
     assert_eq!(format(dec("12.30"), 2).unwrap(), "123{");
     assert_eq!(format(dec("12.3"), 2).unwrap(), "123{");
     assert_eq!(format(dec("1239"), 0).unwrap(), "123I");
-    assert_eq!(format(dec("123"), 0).unwrap(), "123C");
+    assert_eq!(format(dec("123"), 0).unwrap(), "12C");
     assert_eq!(format(pos_zero(), 0).unwrap(), "{");
 }
 
@@ -93,9 +90,62 @@ fn test_format_basic_negative() {
 
 #[test]
 fn test_format_padding() {
-    assert_eq!(format(dec("1.23"), 4).unwrap(), "0123C");
-    assert_eq!(format(dec("0.05"), 2).unwrap(), "0E");
-    assert_eq!(format(dec("-0.05"), 2).unwrap(), "0N");
+    assert_eq!(format(dec("1.23"), 4).unwrap(), "1230{");
+    assert_eq!(format(dec("0.0123"), 4).unwrap(), "0012C");
+    assert_eq!(format(dec("0.05"), 2).unwrap(), "00E");
+    assert_eq!(format(dec("-0.05"), 2).unwrap(), "00N");
+    assert_eq!(format(dec("5"), 2).unwrap(), "50{");
+}
+
+#[test]
+fn test_format_rounding() {
+    assert_eq!(format(dec("1.235"), 2).unwrap(), "12D");
+    assert_eq!(format(dec("1.245"), 2).unwrap(), "12D");
+    assert_eq!(format(dec("-0.004"), 2).unwrap(), "00}");
+}
+
+#[test]
+fn test_format_zero_sign_policy() {
+    let preserve = FormatOptions::default();
+    assert_eq!(
+        format_with_options(neg_zero(), 2, &preserve).unwrap(),
+        "00}"
+    );
+    assert_eq!(
+        format_with_options(dec("-0.001"), 2, &preserve).unwrap(),
+        "00}"
+    );
+    assert_eq!(
+        format_with_options(pos_zero(), 2, &preserve).unwrap(),
+        "00{"
+    );
+
+    let positive = FormatOptions {
+        zero_sign: ZeroSignPolicy::AlwaysPositive,
+        ..FormatOptions::default()
+    };
+    assert_eq!(format_with_options(neg_zero(), 0, &positive).unwrap(), "{");
+    assert_eq!(
+        format_with_options(dec("-0.001"), 2, &positive).unwrap(),
+        "00{"
+    );
+    assert_eq!(
+        format_with_options(dec("-0.01"), 2, &positive).unwrap(),
+        "00J"
+    );
+
+    let negative_zero_only = FormatOptions {
+        zero_sign: ZeroSignPolicy::NegativeZeroOnly,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_with_options(neg_zero(), 1, &negative_zero_only).unwrap(),
+        "0}"
+    );
+    assert_eq!(
+        format_with_options(dec("-0.001"), 2, &negative_zero_only).unwrap(),
+        "00{"
+    );
 }
 
 #[test]
@@ -104,13 +154,45 @@ fn test_format_errors() {
         format(dec("1"), usize::MAX),
         Err(Error::InvalidScale(_))
     ));
-    let large_val = Decimal::from_str("10000000000000000000").unwrap();
-    assert!(matches!(format(large_val, 0), Err(Error::OverflowError(_))));
-    let neg_large_val = Decimal::from_str("-10000000000000000000").unwrap();
+}
+
+#[test]
+fn test_full_decimal_range() {
+    let max = Decimal::MAX;
+    assert_eq!(format(max, 0).unwrap(), "7922816251426433759354395033E");
+    assert_eq!(extract("7922816251426433759354395033E", 0).unwrap(), max);
+    assert_eq!(extract("7922816251426433759354395033N", 0).unwrap(), -max);
+    assert_eq!(
+        format(dec("-10000000000000000000"), 0).unwrap(),
+        "1000000000000000000}"
+    );
+    assert_eq!(
+        extract("123456789012345678901234567I", 20).unwrap(),
+        dec("12345678.90123456789012345679")
+    );
+    assert!(matches!(
+        extract("7922816251426433759354395033F", 0),
+        Err(Error::OverflowError(_))
+    ));
     assert!(matches!(
-        format(neg_large_val, 0),
+        extract("1234567890123456789012345678901A", 0),
         Err(Error::OverflowError(_))
     ));
+    assert_eq!(extract("123A", 29), Err(Error::InvalidScale(29)));
+    assert_eq!(format(Decimal::ONE, 28).unwrap().len(), 29);
+    assert_eq!(format(Decimal::ONE, 29), Err(Error::InvalidScale(29)));
+    assert_eq!(
+        format(Decimal::ONE, 3_000_000_000),
+        Err(Error::InvalidScale(3_000_000_000))
+    );
+    assert_eq!(
+        packed::pack_into(&mut [0u8; 16], Decimal::ONE, 29, &FormatOptions::default()),
+        Err(Error::InvalidScale(29))
+    );
+    assert_eq!(
+        format_into(&mut [0u8; 30], Decimal::ONE, 29),
+        Err(Error::InvalidScale(29))
+    );
 }
 
 #[test]
@@ -124,17 +206,17 @@ fn test_convert_functions() {
         dec("180.592")
     );
     assert_eq!(
-        convert_from_signed_format("123R", "s9(3)").unwrap(),
+        convert_from_signed_format("123R", "s9(4)").unwrap(),
         dec("-1239")
     );
 
     assert_eq!(
         convert_to_signed_format(dec("225.8"), "s9(7)v99").unwrap(),
-        "2258{"
+        "00002258{"
     );
     assert_eq!(
         convert_to_signed_format(dec("180.592"), "9(7)v999").unwrap(),
-        "18059B"
+        "000018059B"
     );
     assert_eq!(
         convert_to_signed_format(dec("-1239"), "s9(4)").unwrap(),
@@ -146,24 +228,223 @@ fn test_convert_functions() {
 fn test_convert_format_errors() {
     assert!(matches!(
         convert_from_signed_format("123{", "s9(1)v9a"),
-        Err(Error::InvalidFormatString(_))
+        Err(Error::InvalidFormatString { .. })
     ));
     assert!(matches!(
         convert_to_signed_format(dec("1"), "s9(1)v9a"),
-        Err(Error::InvalidFormatString(_))
+        Err(Error::InvalidFormatString { .. })
     ));
-    assert!(matches!(
+    assert_eq!(
         convert_from_signed_format("123{", "xxx"),
-        Err(Error::InvalidFormatString(_))
-    ));
-    assert!(matches!(
+        Err(Error::NonNumericPicture(Category::Alphanumeric))
+    );
+    assert_eq!(
         convert_to_signed_format(dec("1"), "xxx"),
-        Err(Error::InvalidFormatString(_))
-    ));
+        Err(Error::NonNumericPicture(Category::Alphanumeric))
+    );
     assert!(convert_from_signed_format("123{", "s9(5)").is_ok());
     assert!(convert_to_signed_format(dec("1"), "s9(5)").is_ok());
 }
 
+#[test]
+fn test_convert_enforces_field_width() {
+    assert_eq!(convert_to_signed_format(dec("0"), "s9(3)").unwrap(), "00{");
+    assert_eq!(
+        convert_to_signed_format(dec("-0.05"), "s9(3)v99").unwrap(),
+        "0000N"
+    );
+    assert_eq!(
+        convert_to_signed_format(dec("99999.99"), "s9(3)v99"),
+        Err(Error::FieldTooNarrow {
+            required: 7,
+            available: 5
+        })
+    );
+    assert_eq!(
+        convert_to_signed_format(dec("1000"), "9(3)"),
+        Err(Error::FieldTooNarrow {
+            required: 4,
+            available: 3
+        })
+    );
+}
+
+#[test]
+fn test_convert_truncates_high_order_digits() {
+    let options = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("-12345.67"), "s9(3)v99", &options).unwrap(),
+        "3456P"
+    );
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("1000"), "9(3)", &options).unwrap(),
+        "00{"
+    );
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("12.5"), "s9(3)v99", &options).unwrap(),
+        "0125{"
+    );
+}
+
+#[test]
+fn test_leading_sign_position() {
+    let parse = ParseOptions {
+        sign_position: SignPosition::LeadingOverpunch,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        extract_with_options("J234", 2, &parse).unwrap(),
+        dec("-12.34")
+    );
+    assert_eq!(
+        extract_with_options("A234", 2, &parse).unwrap(),
+        dec("12.34")
+    );
+    assert_eq!(extract_with_options("}", 0, &parse).unwrap(), dec("-0"));
+    assert_eq!(
+        extract_with_options("123J", 2, &parse),
+        Err(Error::ParseError {
+            invalid_char: 'J',
+            index: 3
+        })
+    );
+
+    let format = FormatOptions {
+        sign_position: SignPosition::LeadingOverpunch,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_with_options(dec("-12.34"), 2, &format).unwrap(),
+        "J234"
+    );
+    assert_eq!(format_with_options(dec("0.05"), 2, &format).unwrap(), "{05");
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("-12.34"), "s9(5)v99", &format).unwrap(),
+        "}001234"
+    );
+    assert_eq!(
+        convert_from_signed_format_with_options("}001234", "s9(5)v99", &parse).unwrap(),
+        dec("-12.34")
+    );
+}
+
+#[test]
+fn test_separate_sign_positions() {
+    let leading = ParseOptions {
+        sign_position: SignPosition::LeadingSeparate,
+        ..ParseOptions::default()
+    };
+    let trailing = ParseOptions {
+        sign_position: SignPosition::TrailingSeparate,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        extract_with_options("-0012345", 2, &leading).unwrap(),
+        dec("-123.45")
+    );
+    assert_eq!(
+        extract_with_options("+0012345", 2, &leading).unwrap(),
+        dec("123.45")
+    );
+    assert_eq!(
+        extract_with_options("0012345+", 0, &trailing).unwrap(),
+        dec("12345")
+    );
+    assert_eq!(
+        extract_with_options("0012345-", 0, &trailing).unwrap(),
+        dec("-12345")
+    );
+    assert_eq!(
+        extract_with_options("0012345", 0, &leading),
+        Err(Error::ParseError {
+            invalid_char: '0',
+            index: 0
+        })
+    );
+    assert_eq!(
+        extract_with_options("001234E", 0, &trailing),
+        Err(Error::ParseError {
+            invalid_char: 'E',
+            index: 6
+        })
+    );
+    assert_eq!(
+        extract_with_options("-", 0, &leading),
+        Err(Error::EmptyField)
+    );
+
+    let format = FormatOptions {
+        sign_position: SignPosition::LeadingSeparate,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_with_options(dec("-123.45"), 2, &format).unwrap(),
+        "-12345"
+    );
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("-123.45"), "s9(5)v99", &format).unwrap(),
+        "-0012345"
+    );
+
+    let format = FormatOptions {
+        sign_position: SignPosition::TrailingSeparate,
+        ..FormatOptions::default()
+    };
+    assert_eq!(format_with_options(dec("0"), 0, &format).unwrap(), "0+");
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("12345"), "s9(7)", &format).unwrap(),
+        "0012345+"
+    );
+
+    // Pictures without `S` have no separate sign, so the output keeps the picture's width
+    for position in [
+        SignPosition::LeadingSeparate,
+        SignPosition::TrailingSeparate,
+    ] {
+        let format = FormatOptions {
+            sign_position: position,
+            ..FormatOptions::default()
+        };
+        let formatted = convert_to_signed_format_with_options(dec("123"), "9(3)", &format).unwrap();
+        assert_eq!(formatted, "123");
+        assert_eq!(formatted.len(), Picture::parse("9(3)").unwrap().width());
+
+        let parse = ParseOptions {
+            sign_position: position,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            convert_from_signed_format_with_options(&formatted, "9(3)", &parse),
+            Ok(dec("123"))
+        );
+    }
+}
+
+#[test]
+fn test_options_select_encoding() {
+    let parse = ParseOptions {
+        encoding: &AsciiSignLast,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        extract_with_options("123q", 2, &parse).unwrap(),
+        dec("-12.31")
+    );
+
+    let format = FormatOptions {
+        encoding: &AsciiSignLast,
+        sign_position: SignPosition::LeadingOverpunch,
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        format_with_options(dec("-12.31"), 2, &format).unwrap(),
+        "q231"
+    );
+}
+
 #[derive(Debug, Clone, Copy)]
 struct AsciiSignLast;
 
@@ -171,7 +452,7 @@ impl Encoding for AsciiSignLast {
     fn encode(&self, digit: u8, sign: Sign) -> Result<char, Error> {
         let base_char = std::char::from_digit(u32::from(digit), 10).unwrap_or('0');
         match sign {
-            Sign::Positive => Ok(base_char),
+            Sign::Positive | Sign::Unsigned => Ok(base_char),
             Sign::Negative => match digit {
                 0 => Ok('p'),
                 1 => Ok('q'),
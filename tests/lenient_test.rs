@@ -0,0 +1,218 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::copybook::RecordLayout;
+use overpunch_ng::error::Error;
+use overpunch_ng::record::{FieldValue, RecordDecoder};
+use overpunch_ng::{
+    convert_from_signed_format_opt, convert_from_signed_format_opt_with_options,
+    convert_from_signed_format_with_report, extract_bytes_opt, extract_bytes_opt_with_encoding,
+    extract_bytes_with_report, extract_opt, extract_opt_with_options, extract_with_options,
+    extract_with_report, Adjustments, BlankPolicy, Extraction, Normalization, ParseOptions,
+    ZonedEbcdic,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn with(normalization: Normalization) -> ParseOptions {
+    ParseOptions {
+        normalization,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_strict_by_default() {
+    let options = ParseOptions::default();
+    for raw in [" 123", "123 ", "1 3", "   "] {
+        assert!(
+            matches!(
+                extract_with_report(raw, 0, &options),
+                Err(Error::ParseError {
+                    invalid_char: ' ',
+                    ..
+                })
+            ),
+            "{raw:?}"
+        );
+    }
+    assert_eq!(
+        extract_with_report("123J", 2, &options),
+        Ok(Extraction {
+            value: Some(dec("-12.31")),
+            adjustments: Adjustments::default(),
+        })
+    );
+}
+
+#[test]
+fn test_trim_and_spaces_as_zeros() {
+    let trim = with(Normalization {
+        trim: true,
+        ..Default::default()
+    });
+    let result = extract_with_report("  123J ", 2, &trim).unwrap();
+    assert_eq!(result.value, Some(dec("-12.31")));
+    assert_eq!(
+        result.adjustments,
+        Adjustments {
+            trimmed: true,
+            ..Default::default()
+        }
+    );
+    // Embedded spaces are still rejected, at their index in the untrimmed field
+    assert_eq!(
+        extract_with_report("  1 3", 0, &trim),
+        Err(Error::ParseError {
+            invalid_char: ' ',
+            index: 3
+        })
+    );
+    assert_eq!(extract_with_report("   ", 0, &trim), Err(Error::EmptyField));
+
+    let zeros = with(Normalization {
+        spaces_as_zeros: true,
+        ..Default::default()
+    });
+    let result = extract_with_report(" 1 3", 0, &zeros).unwrap();
+    assert_eq!(result.value, Some(dec("103")));
+    assert!(result.adjustments.spaces_as_zeros && !result.adjustments.trimmed);
+    assert_eq!(extract_with_options("12 ", 1, &zeros), Ok(dec("12.0")));
+
+    let unchanged = extract_with_report("123", 0, &zeros).unwrap();
+    assert!(unchanged.adjustments.is_empty());
+}
+
+#[test]
+fn test_blank_and_sentinel_fields() {
+    let options = with(Normalization {
+        blank: BlankPolicy::Zero,
+        low_values: BlankPolicy::Missing,
+        high_values: BlankPolicy::Missing,
+        ..Default::default()
+    });
+
+    let blank = extract_with_report("    ", 2, &options).unwrap();
+    assert_eq!(blank.value, Some(dec("0.00")));
+    assert!(blank.adjustments.blank);
+
+    let low = extract_bytes_with_report(&[0x00; 4], 2, &ZonedEbcdic, &options).unwrap();
+    assert_eq!(
+        low,
+        Extraction {
+            value: None,
+            adjustments: Adjustments {
+                low_values: true,
+                ..Default::default()
+            },
+        }
+    );
+    let high = extract_bytes_with_report(&[0xFF; 4], 2, &ZonedEbcdic, &options).unwrap();
+    assert_eq!(high.value, None);
+    assert!(high.adjustments.high_values);
+
+    // EBCDIC blanks are 0x40
+    let blank = extract_bytes_with_report(&[0x40; 3], 0, &ZonedEbcdic, &options).unwrap();
+    assert_eq!(blank.value, Some(dec("0")));
+
+    // Functions returning a plain Decimal cannot represent a missing value
+    assert_eq!(
+        extract_with_options("\0\0\0", 0, &options),
+        Err(Error::EmptyField)
+    );
+
+    // A rejected blank is decoded like any other field
+    let rejected = with(Normalization {
+        low_values: BlankPolicy::Missing,
+        spaces_as_zeros: true,
+        ..Default::default()
+    });
+    let blank = extract_with_report("   ", 0, &rejected).unwrap();
+    assert_eq!(blank.value, Some(dec("0")));
+    assert!(blank.adjustments.spaces_as_zeros && !blank.adjustments.blank);
+}
+
+#[test]
+fn test_lenient_pictures_and_records() {
+    let options = with(Normalization::lenient());
+    let result = convert_from_signed_format_with_report(" 12J", "S9(3)PP", &options).unwrap();
+    assert_eq!(result.value, Some(dec("-12100")));
+    assert!(result.adjustments.trimmed);
+    let result = convert_from_signed_format_with_report("\0\0\0", "S9(3)PP", &options).unwrap();
+    assert_eq!(result.value, None);
+
+    let layout = RecordLayout::parse("01 R. 05 A PIC S9(3)V99. 05 B PIC 9(4).").unwrap();
+    let record = [0x40, 0xF1, 0x40, 0xF2, 0xD3, 0x40, 0x40, 0x40, 0x40];
+    let decoder = RecordDecoder::with_options(layout.clone(), options);
+    let values = decoder.decode_bytes(&record).unwrap();
+    assert_eq!(values.get("A"), Some(&FieldValue::Number(dec("-10.23"))));
+    assert_eq!(values.get("B"), Some(&FieldValue::Number(dec("0"))));
+
+    assert!(RecordDecoder::new(layout).decode_bytes(&record).is_err());
+}
+
+#[test]
+fn test_extract_opt() {
+    assert_eq!(extract_opt("123J", 2), Ok(Some(dec("-12.31"))));
+    assert_eq!(extract_opt("", 2), Ok(None));
+    assert_eq!(extract_opt("     ", 2), Ok(None));
+    assert_eq!(extract_opt("\0\0\0", 2), Ok(None));
+    assert_eq!(
+        extract_opt("12 3", 0),
+        Err(Error::ParseError {
+            invalid_char: ' ',
+            index: 2
+        })
+    );
+    assert_eq!(extract_bytes_opt(&[0x40, 0x40], 0), Ok(None));
+    assert_eq!(extract_bytes_opt(&[0x00, 0x00], 0), Ok(None));
+    assert_eq!(extract_bytes_opt(&[0xF1, 0xD2], 0), Ok(Some(dec("-12"))));
+    assert_eq!(convert_from_signed_format_opt("   ", "S9(3)"), Ok(None));
+    assert_eq!(
+        convert_from_signed_format_opt("12J", "S9(3)PP"),
+        Ok(Some(dec("-12100")))
+    );
+}
+
+#[test]
+fn test_extract_opt_with_sentinels() {
+    let options = with(Normalization {
+        null_sentinel: Some("99999"),
+        high_values: BlankPolicy::Missing,
+        ..Default::default()
+    });
+    assert_eq!(extract_opt_with_options("99999", 2, &options), Ok(None));
+    assert_eq!(
+        extract_opt_with_options("99998", 2, &options),
+        Ok(Some(dec("999.98")))
+    );
+    assert_eq!(
+        extract_opt_with_options("\u{FF}\u{FF}", 2, &options),
+        Ok(None)
+    );
+    assert_eq!(
+        extract_bytes_opt_with_encoding(&[0xF9; 5], 0, &ZonedEbcdic, &options),
+        Ok(None)
+    );
+    assert_eq!(
+        convert_from_signed_format_opt_with_options("99999", "9(5)", &options),
+        Ok(None)
+    );
+
+    let report = extract_with_report("99999", 0, &options).unwrap();
+    assert_eq!(report.value, None);
+    assert!(report.adjustments.null_sentinel);
+
+    // An explicit policy wins over the null default
+    let zero_blanks = with(Normalization {
+        blank: BlankPolicy::Zero,
+        ..Default::default()
+    });
+    assert_eq!(
+        extract_opt_with_options("   ", 1, &zero_blanks),
+        Ok(Some(dec("0.0")))
+    );
+}
@@ -0,0 +1,159 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::packed::{pack_into, pack_with_picture, unpack, unpack_with_picture};
+use overpunch_ng::{FormatOptions, OverflowPolicy, PackedSign, Picture, Usage};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn pic(s: &str) -> Picture {
+    Picture::parse(s).unwrap()
+}
+
+#[test]
+fn test_unpack() {
+    assert_eq!(unpack(&[0x12, 0x34, 0x5C], 2).unwrap(), dec("123.45"));
+    assert_eq!(unpack(&[0x12, 0x34, 0x5D], 2).unwrap(), dec("-123.45"));
+    assert_eq!(unpack(&[0x01, 0x23, 0x4F], 0).unwrap(), dec("1234"));
+    assert_eq!(unpack(&[0x7A], 0).unwrap(), dec("7"));
+    assert_eq!(unpack(&[0x7B], 0).unwrap(), dec("-7"));
+    assert_eq!(unpack(&[0x00, 0x0D], 1).unwrap(), -Decimal::ZERO);
+    assert!(unpack(&[0x00, 0x0D], 1).unwrap().is_sign_negative());
+}
+
+#[test]
+fn test_unpack_errors() {
+    assert_eq!(unpack(&[], 0), Err(Error::EmptyField));
+    assert_eq!(
+        unpack(&[0x1A, 0x2C], 0),
+        Err(Error::InvalidByte {
+            byte: 0x1A,
+            index: 0
+        })
+    );
+    assert_eq!(
+        unpack(&[0x12, 0x34], 0),
+        Err(Error::InvalidByte {
+            byte: 0x34,
+            index: 1
+        })
+    );
+    assert_eq!(
+        unpack(&[0x12, 0xA3], 0),
+        Err(Error::InvalidByte {
+            byte: 0xA3,
+            index: 1
+        })
+    );
+    assert!(matches!(
+        unpack(&[0x99; 16], 0),
+        Err(Error::OverflowError(_))
+    ));
+    assert_eq!(unpack(&[0x1C], 29), Err(Error::InvalidScale(29)));
+}
+
+#[test]
+fn test_pack_into() {
+    let options = FormatOptions::default();
+    let mut buf = [0u8; 3];
+    pack_into(&mut buf, dec("123.45"), 2, &options).unwrap();
+    assert_eq!(buf, [0x12, 0x34, 0x5C]);
+
+    pack_into(&mut buf, dec("-1.5"), 2, &options).unwrap();
+    assert_eq!(buf, [0x00, 0x15, 0x0D]);
+
+    let unsigned = FormatOptions {
+        packed_sign: PackedSign::Unsigned,
+        ..FormatOptions::default()
+    };
+    pack_into(&mut buf, dec("42"), 0, &unsigned).unwrap();
+    assert_eq!(buf, [0x00, 0x04, 0x2F]);
+
+    assert_eq!(
+        pack_into(&mut buf, dec("123456"), 0, &options),
+        Err(Error::FieldTooNarrow {
+            required: 6,
+            available: 5
+        })
+    );
+
+    let truncate = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    pack_into(&mut buf, dec("-123456"), 0, &truncate).unwrap();
+    assert_eq!(buf, [0x23, 0x45, 0x6D]);
+}
+
+#[test]
+fn test_picture_driven_packing() {
+    let picture = pic("PIC S9(5)V99 COMP-3");
+    assert_eq!(
+        pack_with_picture(dec("-12.3"), &picture, &FormatOptions::default()).unwrap(),
+        vec![0x00, 0x01, 0x23, 0x0D]
+    );
+    assert_eq!(
+        unpack_with_picture(&[0x00, 0x01, 0x23, 0x0D], &picture).unwrap(),
+        dec("-12.30")
+    );
+
+    // An even digit count leaves the high-order nibble unused
+    let picture = pic("S9(4) COMP-3");
+    assert_eq!(
+        pack_with_picture(dec("1234"), &picture, &FormatOptions::default()).unwrap(),
+        vec![0x01, 0x23, 0x4C]
+    );
+    assert_eq!(
+        pack_with_picture(dec("12345"), &picture, &FormatOptions::default()),
+        Err(Error::FieldTooNarrow {
+            required: 5,
+            available: 4
+        })
+    );
+
+    let picture = pic("S9(3)PP COMP-3");
+    assert_eq!(
+        unpack_with_picture(&[0x12, 0x3C], &picture).unwrap(),
+        dec("12300")
+    );
+
+    assert_eq!(
+        unpack_with_picture(&[0x12, 0x3C], &pic("S9(3)")),
+        Err(Error::UnsupportedUsage(Usage::Display))
+    );
+}
+
+#[test]
+fn test_packed_roundtrip() {
+    let options = FormatOptions::default();
+    for raw in ["0", "-0.01", "99999.99", "-12345.67", "42"] {
+        let value = dec(raw);
+        let mut buf = [0u8; 4];
+        pack_into(&mut buf, value, 2, &options).unwrap();
+        assert_eq!(unpack(&buf, 2).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_unpack_validates_digits_against_picture() {
+    // S9(4) occupies three bytes, whose first nibble must stay zero
+    assert_eq!(
+        unpack_with_picture(&[0x12, 0x34, 0x5C], &pic("S9(4) COMP-3")),
+        Err(Error::TooManyDigits {
+            allowed: 4,
+            actual: 5
+        })
+    );
+    assert_eq!(
+        unpack_with_picture(&[0x01, 0x23, 0x4D], &pic("S9(4) COMP-3")),
+        Ok(dec("-1234"))
+    );
+    assert_eq!(
+        unpack_with_picture(&[0x00, 0x0C], &pic("S9(2) COMP-3")),
+        Ok(dec("0"))
+    );
+}
@@ -0,0 +1,287 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    binary, convert_from_signed_format, convert_from_signed_format_with_options,
+    convert_to_signed_format, packed, Category, FormatOptions, ParseOptions, Picture, SignCheck,
+    SignPosition, Usage,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn column_of(result: Result<Picture, Error>) -> usize {
+    match result {
+        Err(Error::InvalidFormatString { column, .. }) => column,
+        other => panic!("expected InvalidFormatString, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_basic_pictures() {
+    let picture = Picture::parse("S9(7)V99").unwrap();
+    assert!(picture.is_signed());
+    assert_eq!(picture.integer_digits(), 7);
+    assert_eq!(picture.fraction_digits(), 2);
+    assert_eq!(picture.scale(), 2);
+    assert_eq!(picture.width(), 9);
+    assert_eq!(picture.usage(), Usage::Display);
+
+    let picture = Picture::parse("9999V9(3)").unwrap();
+    assert!(!picture.is_signed());
+    assert_eq!(picture.integer_digits(), 4);
+    assert_eq!(picture.fraction_digits(), 3);
+
+    let picture = Picture::parse("SV999").unwrap();
+    assert!(picture.is_signed());
+    assert_eq!(picture.integer_digits(), 0);
+    assert_eq!(picture.fraction_digits(), 3);
+
+    assert_eq!(
+        Picture::parse("s9(3)v9(2)").unwrap(),
+        Picture::parse("S999V99").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_keywords_and_usage() {
+    let picture = Picture::parse("  PIC S9(5)V99 COMP-3  ").unwrap();
+    assert_eq!(picture.usage(), Usage::PackedDecimal);
+    assert_eq!(picture.digits(), 7);
+    assert_eq!(picture.width(), 4);
+
+    let picture = Picture::parse("picture is 9(4) usage is binary.").unwrap();
+    assert_eq!(picture.usage(), Usage::Binary);
+    assert_eq!(picture.width(), 2);
+
+    assert_eq!(
+        Picture::parse("PIC S9(10) COMP-5").unwrap().usage(),
+        Usage::NativeBinary
+    );
+    assert_eq!(Picture::parse("PIC S9(10) COMP-5").unwrap().width(), 8);
+    assert_eq!(
+        Picture::parse("PIC 99 DISPLAY.").unwrap().usage(),
+        Usage::Display
+    );
+    assert_eq!(Picture::from_str("9(3)").unwrap().digits(), 3);
+}
+
+#[test]
+fn test_parse_p_scaling() {
+    let picture = Picture::parse("SVPP99").unwrap();
+    assert_eq!(picture.fraction_digits(), 2);
+    assert_eq!(picture.scale(), 4);
+
+    assert_eq!(Picture::parse("PPP9").unwrap().scale(), 4);
+
+    let picture = Picture::parse("S99PPP").unwrap();
+    assert_eq!(picture.integer_digits(), 2);
+    assert_eq!(picture.scale(), -3);
+    assert_eq!(picture.width(), 2);
+}
+
+#[test]
+fn test_parse_errors_point_at_column() {
+    assert_eq!(column_of(Picture::parse("s9(3)v9(2")), 8);
+    assert_eq!(column_of(Picture::parse("s9(1)v9a")), 8);
+    assert_eq!(column_of(Picture::parse("9S9")), 2);
+    assert_eq!(column_of(Picture::parse("9V9V9")), 4);
+    assert_eq!(column_of(Picture::parse("9()")), 3);
+    assert_eq!(column_of(Picture::parse("9(0)")), 3);
+    assert_eq!(column_of(Picture::parse("9P9")), 3);
+    assert_eq!(column_of(Picture::parse("PP9V9")), 4);
+    assert_eq!(column_of(Picture::parse("S")), 2);
+    assert_eq!(column_of(Picture::parse("")), 1);
+    assert_eq!(column_of(Picture::parse("PIC 9(5) COMP-9")), 10);
+    assert_eq!(column_of(Picture::parse("PIC 9(5) COMP extra")), 15);
+    assert_eq!(column_of(Picture::parse("9(19) COMP")), 1);
+    assert_eq!(column_of(Picture::parse("9(39)")), 3);
+    assert_eq!(column_of(Picture::parse("9(20)9(20)")), 1);
+}
+
+#[test]
+fn test_convert_with_p_scaling() {
+    assert_eq!(
+        convert_from_signed_format("12C", "S9(3)PP").unwrap(),
+        dec("12300")
+    );
+    assert_eq!(
+        convert_from_signed_format("12C", "SVPP999").unwrap(),
+        dec("0.00123")
+    );
+    assert_eq!(
+        convert_to_signed_format(dec("-45600"), "S9(3)PP").unwrap(),
+        "45O"
+    );
+}
+
+#[test]
+fn test_convert_rejects_non_display_usage() {
+    assert_eq!(
+        convert_from_signed_format("123C", "S9(4) COMP-3"),
+        Err(Error::UnsupportedUsage(Usage::PackedDecimal))
+    );
+    assert_eq!(
+        convert_to_signed_format(dec("1"), "S9(4) COMP"),
+        Err(Error::UnsupportedUsage(Usage::Binary))
+    );
+}
+
+#[test]
+fn test_parse_alphanumeric_pictures() {
+    let picture = Picture::parse("PIC X(10)").unwrap();
+    assert_eq!(picture.category(), Category::Alphanumeric);
+    assert!(!picture.is_numeric());
+    assert_eq!(picture.width(), 10);
+    assert_eq!(picture.digits(), 0);
+
+    assert_eq!(
+        Picture::parse("A(3)").unwrap().category(),
+        Category::Alphabetic
+    );
+    assert_eq!(Picture::parse("99XX9").unwrap().width(), 5);
+    assert_eq!(Picture::parse("X(200)").unwrap().width(), 200);
+
+    assert_eq!(column_of(Picture::parse("S9X")), 3);
+    assert_eq!(column_of(Picture::parse("X9V9")), 3);
+    assert_eq!(column_of(Picture::parse("X(4) COMP-3")), 1);
+
+    assert_eq!(
+        convert_from_signed_format("ABC", "X(3)"),
+        Err(Error::NonNumericPicture(Category::Alphanumeric))
+    );
+}
+
+#[test]
+fn test_display_round_trips() {
+    let cases = [
+        ("S9(7)V99", "S9(7)V9(2)"),
+        ("9999", "9(4)"),
+        ("SV999", "SV9(3)"),
+        ("PIC S9(5) USAGE COMP-3", "S9(5) COMP-3"),
+        ("9(3)PP", "9(3)P(2)"),
+        ("SVPP99", "SVP(2)9(2)"),
+        ("XX", "X(2)"),
+        ("A(5)", "A(5)"),
+        ("S9(4) BINARY", "S9(4) COMP"),
+    ];
+    for (input, expected) in cases {
+        let picture = Picture::parse(input).unwrap();
+        assert_eq!(picture.to_string(), expected, "{input}");
+        assert_eq!(Picture::parse(expected).unwrap(), picture, "{input}");
+    }
+}
+
+#[test]
+fn test_extraction_validates_digits_against_picture() {
+    assert_eq!(
+        convert_from_signed_format("12345678{", "S9(5)V99"),
+        Err(Error::TooManyDigits {
+            allowed: 7,
+            actual: 9
+        })
+    );
+    // Leading zeros still count: the field is the wrong shape
+    assert_eq!(
+        convert_from_signed_format("000123{", "S9(4)V99"),
+        Err(Error::TooManyDigits {
+            allowed: 6,
+            actual: 7
+        })
+    );
+    // Shorter fields are read right-aligned
+    assert_eq!(
+        convert_from_signed_format("123{", "S9(5)V99"),
+        Ok(dec("12.30"))
+    );
+    assert_eq!(
+        convert_from_signed_format("12J", "S9(3)PP"),
+        Ok(dec("-12100"))
+    );
+    assert_eq!(
+        convert_from_signed_format("123J", "S9(3)PP"),
+        Err(Error::TooManyDigits {
+            allowed: 3,
+            actual: 4
+        })
+    );
+
+    // A separate sign is not a digit
+    let options = ParseOptions {
+        sign_position: SignPosition::LeadingSeparate,
+        ..Default::default()
+    };
+    assert_eq!(
+        convert_from_signed_format_with_options("-12345", "S9(5)", &options),
+        Ok(dec("-12345"))
+    );
+}
+
+#[test]
+fn test_extraction_checks_sign_against_picture() {
+    // Unchecked by default
+    assert_eq!(convert_from_signed_format("12J", "9(3)"), Ok(dec("-121")));
+
+    let strict = ParseOptions {
+        sign_check: SignCheck::Strict,
+        ..Default::default()
+    };
+    assert_eq!(
+        convert_from_signed_format_with_options("12J", "9(3)", &strict),
+        Err(Error::UnexpectedSign)
+    );
+    assert_eq!(
+        convert_from_signed_format_with_options("12J", "S9(3)", &strict),
+        Ok(dec("-121"))
+    );
+    assert_eq!(
+        convert_from_signed_format_with_options("12A", "9(3)", &strict),
+        Ok(dec("121"))
+    );
+    assert_eq!(
+        convert_from_signed_format_with_options("00}", "9(3)", &strict),
+        Err(Error::UnexpectedSign)
+    );
+}
+
+#[test]
+fn test_unsigned_pictures_reject_negatives() {
+    let options = FormatOptions::default();
+
+    assert_eq!(
+        convert_to_signed_format(dec("-5"), "9(3)"),
+        Err(Error::UnexpectedSign)
+    );
+    assert_eq!(
+        convert_to_signed_format(dec("-5"), "S9(3)"),
+        Ok("00N".to_string())
+    );
+    // Negative zero is written as positive
+    assert_eq!(
+        convert_to_signed_format(-Decimal::ZERO, "9(3)"),
+        Ok("00{".to_string())
+    );
+
+    let picture = Picture::parse("9(3) COMP-3").unwrap();
+    assert_eq!(
+        packed::pack_with_picture(dec("-5"), &picture, &options),
+        Err(Error::UnexpectedSign)
+    );
+    assert_eq!(
+        packed::pack_with_picture(-Decimal::ZERO, &picture, &options),
+        Ok(vec![0x00, 0x0C])
+    );
+
+    let picture = Picture::parse("9(3) COMP").unwrap();
+    assert_eq!(
+        binary::write_with_picture(dec("-5"), &picture, &options),
+        Err(Error::UnexpectedSign)
+    );
+    assert_eq!(
+        binary::write_with_picture(-Decimal::ZERO, &picture, &options),
+        Ok(vec![0x00, 0x00])
+    );
+}
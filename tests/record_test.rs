@@ -0,0 +1,285 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::copybook::{Field, RecordLayout};
+use overpunch_ng::error::Error;
+use overpunch_ng::record::{FieldValue, Record, RecordDecoder, RecordEncoder};
+use overpunch_ng::{
+    CodePage, FormatOptions, OverflowPolicy, ParseOptions, Picture, SignCheck, SignPosition,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn number(s: &str) -> FieldValue {
+    FieldValue::Number(dec(s))
+}
+
+fn text(s: &str) -> FieldValue {
+    FieldValue::Text(s.to_string())
+}
+
+const COPYBOOK: &str = "
+       01  ACCOUNT.
+           05  ACCOUNT-ID         PIC 9(4).
+           05  HOLDER             PIC X(6).
+           05  FILLER             PIC X(2).
+           05  BALANCE            PIC S9(5)V99 COMP-3.
+           05  RATE               PIC S9V999.
+           05  VISITS             PIC S9(4) COMP.
+";
+
+fn decoder() -> RecordDecoder {
+    RecordDecoder::new(RecordLayout::parse(COPYBOOK).unwrap())
+}
+
+#[test]
+fn test_decode_bytes() {
+    let mut raw = vec![0xF0, 0xF0, 0xF4, 0xF2];
+    raw.extend([0xC1, 0xD3, 0xC9, 0xC3, 0xC5, 0x40]); // "ALICE "
+    raw.extend([0x40, 0x40]);
+    raw.extend([0x01, 0x23, 0x45, 0x6D]);
+    raw.extend([0xF0, 0xF1, 0xF2, 0xC5]);
+    raw.extend([0x00, 0x2A]);
+
+    let record = decoder().decode_bytes(&raw).unwrap();
+    assert_eq!(record.len(), 5);
+    assert_eq!(record.get("ACCOUNT-ID"), Some(&number("42")));
+    assert_eq!(record.get("holder"), Some(&text("ALICE ")));
+    assert_eq!(record.get("BALANCE"), Some(&number("-1234.56")));
+    assert_eq!(record.get("RATE"), Some(&number("0.125")));
+    assert_eq!(record.get("VISITS"), Some(&number("42")));
+    assert_eq!(record.get("FILLER"), None);
+
+    let names: Vec<&str> = record.iter().map(|(name, _)| name).collect();
+    assert_eq!(
+        names,
+        vec!["ACCOUNT-ID", "HOLDER", "BALANCE", "RATE", "VISITS"]
+    );
+}
+
+#[test]
+fn test_decode_ascii_bytes() {
+    let options = ParseOptions {
+        byte_encoding: &overpunch_ng::Ebcdic,
+        code_page: CodePage::Latin1,
+        ..ParseOptions::default()
+    };
+    let layout = RecordLayout::parse("01 R. 05 NAME PIC X(3). 05 AMOUNT PIC S9(3)V9.").unwrap();
+    let record = RecordDecoder::with_options(layout, options)
+        .decode_bytes(b"BOB123J")
+        .unwrap();
+    assert_eq!(record.get("NAME"), Some(&text("BOB")));
+    assert_eq!(record.get("AMOUNT"), Some(&number("-123.1")));
+}
+
+#[test]
+fn test_decode_str() {
+    let layout = RecordLayout::parse(
+        "
+       01  LINE-ITEM.
+           05  SKU                PIC X(4).
+           05  QTY                PIC S9(3) SIGN LEADING SEPARATE.
+           05  PRICE              PIC S9(3)V99.
+           05  NOTE               PIC X(3).
+",
+    )
+    .unwrap();
+    let record = RecordDecoder::new(layout)
+        .decode_str("ÅB-1-0051234Nzzz")
+        .unwrap();
+    assert_eq!(record.get("SKU"), Some(&text("ÅB-1")));
+    assert_eq!(record.get("QTY"), Some(&number("-5")));
+    assert_eq!(record.get("PRICE"), Some(&number("-123.45")));
+    assert_eq!(record.get("NOTE"), Some(&text("zzz")));
+}
+
+#[test]
+fn test_errors_carry_field_context() {
+    let raw = [
+        0xF0, 0xF0, 0xF4, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x01, 0x23, 0x45,
+        0x6D, 0xF0, 0xF1, 0xF2, 0xC5, 0x00, 0x2A,
+    ];
+    assert_eq!(
+        decoder().decode_bytes(&raw),
+        Err(Error::FieldError {
+            field: "ACCOUNT-ID".to_string(),
+            offset: 0,
+            source: Box::new(Error::InvalidByte {
+                byte: 0x40,
+                index: 3
+            }),
+        })
+    );
+
+    assert_eq!(
+        decoder().decode_bytes(&raw[..10]),
+        Err(Error::RecordTooShort {
+            required: 22,
+            actual: 10
+        })
+    );
+
+    match decoder().decode_str("0042ALICE   12345{0125E00") {
+        Err(Error::FieldError {
+            field,
+            offset,
+            source,
+        }) => {
+            assert_eq!((field.as_str(), offset), ("BALANCE", 12));
+            assert_eq!(
+                *source,
+                Error::UnsupportedUsage(overpunch_ng::Usage::PackedDecimal)
+            );
+        }
+        other => panic!("expected FieldError, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_strict_sign_check_covers_packed_fields() {
+    let layout = RecordLayout::parse("01 R. 05 COUNT PIC 9(3) COMP-3.").unwrap();
+    let options = ParseOptions {
+        sign_check: SignCheck::Strict,
+        ..Default::default()
+    };
+    let decoder = RecordDecoder::with_options(layout, options);
+    assert_eq!(
+        decoder.decode_bytes(&[0x12, 0x3F]).unwrap().get("COUNT"),
+        Some(&number("123"))
+    );
+    assert_eq!(
+        decoder.decode_bytes(&[0x12, 0x3D]),
+        Err(Error::FieldError {
+            field: "COUNT".to_string(),
+            offset: 0,
+            source: Box::new(Error::UnexpectedSign),
+        })
+    );
+}
+
+#[test]
+fn test_record_collects_values() {
+    let mut record: Record = [("A", number("1")), ("B", text("x"))].into_iter().collect();
+    record.insert("a", number("2"));
+    assert_eq!(record.len(), 2);
+    assert_eq!(record.get("A"), Some(&number("2")));
+}
+
+#[test]
+fn test_encode_bytes() {
+    let mut record = Record::new();
+    record.insert("ACCOUNT-ID", dec("42").into());
+    record.insert("HOLDER", "ALICE ".into());
+    record.insert("BALANCE", dec("-1234.56").into());
+    record.insert("RATE", dec("0.125").into());
+    record.insert("VISITS", dec("42").into());
+
+    let encoder = RecordEncoder::new(RecordLayout::parse(COPYBOOK).unwrap());
+    let raw = encoder.encode_bytes(&record).unwrap();
+    assert_eq!(
+        raw,
+        vec![
+            0xF0, 0xF0, 0xF4, 0xC2, 0xC1, 0xD3, 0xC9, 0xC3, 0xC5, 0x40, 0x40, 0x40, 0x01, 0x23,
+            0x45, 0x6D, 0xF0, 0xF1, 0xF2, 0xC5, 0x00, 0x2A,
+        ]
+    );
+    assert_eq!(decoder().decode_bytes(&raw).unwrap(), record);
+}
+
+#[test]
+fn test_encode_str_pads_and_initialises() {
+    let layout = RecordLayout::parse(
+        "
+       01  OUT-REC.
+           05  REC-TYPE           PIC X(2) VALUE 'D1'.
+           05  NAME               PIC X(8).
+           05  FILLER             PIC X(2) VALUE '**'.
+           05  AMOUNT             PIC S9(4)V99.
+           05  COUNT-FIELD        PIC 9(3).
+           05  FILLER             PIC X(3).
+           05  QTY                PIC S9(3) SIGN TRAILING SEPARATE.
+",
+    )
+    .unwrap();
+    let encoder = RecordEncoder::new(layout);
+
+    let record: Record = [
+        ("NAME", FieldValue::from("BOB")),
+        ("AMOUNT", dec("-12.3").into()),
+        ("QTY", dec("7").into()),
+        ("FILLER", "XX".into()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        encoder.encode_str(&record).unwrap(),
+        "D1BOB     **00123}00{   007+"
+    );
+}
+
+#[test]
+fn test_encode_errors_carry_field_context() {
+    let layout = RecordLayout::parse("01 R. 05 NAME PIC X(3). 05 AMOUNT PIC S9(3).").unwrap();
+    let encoder = RecordEncoder::new(layout.clone());
+
+    let record: Record = [("NAME", FieldValue::from("ALICE"))].into_iter().collect();
+    assert_eq!(
+        encoder.encode_str(&record),
+        Err(Error::FieldError {
+            field: "NAME".to_string(),
+            offset: 0,
+            source: Box::new(Error::FieldTooNarrow {
+                required: 5,
+                available: 3
+            }),
+        })
+    );
+
+    let record: Record = [("AMOUNT", FieldValue::from("12"))].into_iter().collect();
+    assert!(matches!(
+        encoder.encode_str(&record),
+        Err(Error::FieldError { source, .. }) if *source == Error::ExpectedNumber
+    ));
+
+    let record: Record = [("AMOUNT", FieldValue::from(dec("1234")))]
+        .into_iter()
+        .collect();
+    assert!(matches!(
+        encoder.encode_bytes(&record),
+        Err(Error::FieldError { offset: 3, .. })
+    ));
+
+    let truncate = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    let record: Record = [("NAME", FieldValue::from("ALICE"))].into_iter().collect();
+    assert_eq!(
+        RecordEncoder::with_options(layout, truncate)
+            .encode_str(&record)
+            .unwrap(),
+        "ALI00{"
+    );
+}
+
+#[test]
+fn test_unsigned_fields_have_no_separate_sign() {
+    let mut count = Field::new("COUNT", 0, Picture::parse("9(3)").unwrap());
+    count.sign_position = SignPosition::TrailingSeparate;
+    let layout = RecordLayout::new(vec![count]);
+    assert_eq!(layout.record_length(), 3);
+
+    let mut record = Record::new();
+    record.insert("COUNT", FieldValue::Number(dec("123")));
+    let bytes = RecordEncoder::new(layout.clone())
+        .encode_bytes(&record)
+        .unwrap();
+    assert_eq!(bytes, [0xF1, 0xF2, 0xF3]);
+    assert_eq!(
+        RecordDecoder::new(layout).decode_bytes(&bytes).unwrap(),
+        record
+    );
+}
@@ -0,0 +1,85 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::detect::detect;
+use overpunch_ng::error::Error;
+use overpunch_ng::registry::{self, BUILTIN};
+use overpunch_ng::{
+    extract_with_dyn_encoding, extract_with_options, format_with_dyn_encoding, EncodingTable,
+    ParseOptions, Sign, TableEncoding,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+// Tests share one registry, so each registers under its own name
+fn custom() -> &'static TableEncoding {
+    static CUSTOM: OnceLock<TableEncoding> = OnceLock::new();
+    CUSTOM.get_or_init(|| {
+        TableEncoding::new(EncodingTable {
+            positive: ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'],
+            negative: ['!', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r'],
+            plain_digits: Some(Sign::Positive),
+            ..Default::default()
+        })
+        .unwrap()
+    })
+}
+
+#[test]
+fn test_builtin_lookup() {
+    for (name, encoding) in BUILTIN {
+        assert_eq!(
+            format!("{:?}", registry::lookup(name).unwrap()),
+            format!("{encoding:?}")
+        );
+    }
+    for name in ["microfocus", "MicroFocus", "Micro Focus", "MICRO_FOCUS"] {
+        let encoding = registry::lookup(name).unwrap();
+        assert_eq!(
+            extract_with_dyn_encoding("1234u", 2, encoding),
+            Ok(dec("-123.45"))
+        );
+    }
+    assert_eq!(
+        format_with_dyn_encoding(dec("-1.5"), 1, registry::lookup("IBMi").unwrap()),
+        Ok("1N".to_string())
+    );
+    assert!(matches!(
+        registry::lookup("zoned"),
+        Err(Error::UnknownEncoding(name)) if name == "zoned"
+    ));
+}
+
+#[test]
+fn test_register_custom_encoding() {
+    registry::register("legacy-billing", custom()).unwrap();
+    assert!(registry::names().starts_with(&["ebcdic", "ascii", "micro-focus", "ibm-i"]));
+    assert!(registry::names().contains(&"legacy-billing"));
+
+    let options = ParseOptions {
+        encoding: registry::lookup("Legacy Billing").unwrap(),
+        ..Default::default()
+    };
+    assert_eq!(extract_with_options("123!", 1, &options), Ok(dec("-123.0")));
+
+    // Names are unique however they are spelled
+    assert_eq!(
+        registry::register("LEGACY_BILLING", custom()),
+        Err(Error::DuplicateEncoding("LEGACY_BILLING".to_string()))
+    );
+    assert_eq!(
+        registry::register("EBCDIC", custom()),
+        Err(Error::DuplicateEncoding("EBCDIC".to_string()))
+    );
+}
+
+#[test]
+fn test_detect_includes_registered_encodings() {
+    registry::register("detect-custom", custom()).unwrap();
+    let detection = detect(&["123!", "00045", "12k"]);
+    assert_eq!(detection.best().unwrap().name, "detect-custom");
+}
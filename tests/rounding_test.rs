@@ -0,0 +1,114 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    binary, convert_to_signed_format_with_options, format, format_with_options, packed,
+    FormatOptions, Picture, RoundingMode,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn rounding(mode: RoundingMode) -> FormatOptions {
+    FormatOptions {
+        rounding: mode,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_rounding_modes() {
+    // Each row: value, then the result at 1 decimal under Truncate, HalfUp, HalfEven, Ceiling
+    // and Floor
+    let cases = [
+        ("1.25", ["1.2", "1.3", "1.2", "1.3", "1.2"]),
+        ("-1.25", ["-1.2", "-1.3", "-1.2", "-1.2", "-1.3"]),
+        ("1.35", ["1.3", "1.4", "1.4", "1.4", "1.3"]),
+        ("-1.35", ["-1.3", "-1.4", "-1.4", "-1.3", "-1.4"]),
+        ("1.21", ["1.2", "1.2", "1.2", "1.3", "1.2"]),
+        ("-1.29", ["-1.2", "-1.3", "-1.3", "-1.2", "-1.3"]),
+        ("1.2", ["1.2", "1.2", "1.2", "1.2", "1.2"]),
+    ];
+    let modes = [
+        RoundingMode::Truncate,
+        RoundingMode::HalfUp,
+        RoundingMode::HalfEven,
+        RoundingMode::Ceiling,
+        RoundingMode::Floor,
+    ];
+    for (value, expected) in cases {
+        for (mode, result) in modes.into_iter().zip(expected) {
+            assert_eq!(
+                format_with_options(dec(value), 1, &rounding(mode)),
+                format(dec(result), 1),
+                "{value} {mode:?}"
+            );
+        }
+    }
+    assert_eq!(
+        format_with_options(dec("-0.04"), 1, &rounding(RoundingMode::Floor)),
+        Ok("0J".to_string())
+    );
+}
+
+#[test]
+fn test_default_is_half_even() {
+    assert_eq!(format(dec("1.25"), 1), format(dec("1.2"), 1));
+    assert_eq!(format(dec("1.35"), 1), format(dec("1.4"), 1));
+}
+
+#[test]
+fn test_reject_precision_loss() {
+    let options = rounding(RoundingMode::Reject);
+    assert_eq!(
+        format_with_options(dec("1.20"), 1, &options),
+        Ok("1B".to_string())
+    );
+    assert_eq!(
+        format_with_options(dec("-1.25"), 1, &options),
+        Err(Error::PrecisionLoss {
+            value: "-1.25".to_string(),
+            decimals: 1
+        })
+    );
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("1234"), "9(2)PP", &options),
+        Err(Error::PrecisionLoss {
+            value: "12.34".to_string(),
+            decimals: 0
+        })
+    );
+    assert_eq!(
+        convert_to_signed_format_with_options(dec("1200"), "9(2)PP", &options),
+        Ok("1B".to_string())
+    );
+}
+
+#[test]
+fn test_rounding_applies_to_packed_and_binary() {
+    let picture = Picture::parse("S9(3)V9 COMP-3").unwrap();
+    let half_up = rounding(RoundingMode::HalfUp);
+    assert_eq!(
+        packed::pack_with_picture(dec("-12.25"), &picture, &half_up),
+        Ok(vec![0x00, 0x12, 0x3D])
+    );
+    let truncate = rounding(RoundingMode::Truncate);
+    assert_eq!(
+        packed::pack_with_picture(dec("-12.29"), &picture, &truncate),
+        Ok(vec![0x00, 0x12, 0x2D])
+    );
+
+    let picture = Picture::parse("S9(3)V9 COMP").unwrap();
+    assert_eq!(
+        binary::write_with_picture(dec("-0.05"), &picture, &half_up),
+        Ok(vec![0xFF, 0xFF])
+    );
+    let ceiling = rounding(RoundingMode::Ceiling);
+    assert_eq!(
+        binary::write_with_picture(dec("0.01"), &picture, &ceiling),
+        Ok(vec![0x00, 0x01])
+    );
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "serde")]
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::serde::Overpunch;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Payment {
+    id: String,
+    #[serde(with = "overpunch_ng::serde::s9v2")]
+    amount: Decimal,
+    #[serde(with = "overpunch_ng::serde::s9v0")]
+    count: Decimal,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Balance {
+    total: Overpunch<5, 2>,
+    rate: Overpunch<0, 3>,
+}
+
+#[test]
+fn test_with_modules() {
+    let payment: Payment =
+        serde_json::from_str(r#"{"id":"P1","amount":"1234J","count":"4{"}"#).unwrap();
+    assert_eq!(payment.amount, dec("-123.41"));
+    assert_eq!(payment.count, dec("40"));
+
+    assert_eq!(
+        serde_json::to_string(&payment).unwrap(),
+        r#"{"id":"P1","amount":"1234J","count":"4{"}"#
+    );
+}
+
+#[test]
+fn test_overpunch_newtype_has_fixed_width() {
+    let balance = Balance {
+        total: Overpunch(dec("-12.5")),
+        rate: dec("0.125").into(),
+    };
+    let json = serde_json::to_string(&balance).unwrap();
+    assert_eq!(json, r#"{"total":"000125}","rate":"12E"}"#);
+    assert_eq!(serde_json::from_str::<Balance>(&json).unwrap(), balance);
+
+    let too_wide = Balance {
+        total: Overpunch(dec("123456")),
+        rate: Overpunch(dec("0")),
+    };
+    assert!(serde_json::to_string(&too_wide).is_err());
+}
+
+#[test]
+fn test_invalid_input_is_a_serde_error() {
+    let error =
+        serde_json::from_str::<Payment>(r#"{"id":"P1","amount":"12X4","count":"1A"}"#).unwrap_err();
+    assert!(error.to_string().contains("invalid character 'X'"));
+    assert!(serde_json::from_str::<Payment>(r#"{"id":"P1","amount":12,"count":"1A"}"#).is_err());
+}
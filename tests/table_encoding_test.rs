@@ -0,0 +1,131 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    extract_with_options, format_with_dyn_encoding, format_with_options, Encoding, EncodingTable,
+    FormatOptions, ParseOptions, Sign, TableEncoding,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn chars(s: &str) -> [char; 10] {
+    s.chars().collect::<Vec<_>>().try_into().unwrap()
+}
+
+#[test]
+fn test_plain_digits_and_non_ascii_symbols() {
+    let strict = TableEncoding::new(EncodingTable {
+        plain_digits: None,
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(strict.decode('5'), Err(Error::UnsupportedCharacter('5')));
+    assert_eq!(strict.decode_digit('5'), Ok(5));
+
+    let negative = TableEncoding::new(EncodingTable {
+        plain_digits: Some(Sign::Negative),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(negative.decode('5'), Ok((5, Sign::Negative)));
+
+    let accented = TableEncoding::new(EncodingTable {
+        positive: chars("äABCDEFGHI"),
+        negative: chars("üJKLMNOPQR"),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(accented.decode('ü'), Ok((0, Sign::Negative)));
+    assert_eq!(accented.decode('{'), Err(Error::UnsupportedCharacter('{')));
+    assert_eq!(
+        format_with_dyn_encoding(dec("-12.30"), 2, &accented),
+        Ok("123ü".to_string())
+    );
+}
+
+#[test]
+fn test_invalid_tables() {
+    let duplicate = EncodingTable {
+        negative: chars("}JKLMNOPQA"),
+        ..Default::default()
+    };
+    assert_eq!(
+        TableEncoding::new(duplicate).unwrap_err(),
+        Error::InvalidEncodingTable {
+            symbol: 'A',
+            reason: "character appears more than once"
+        }
+    );
+
+    let cases = [
+        (
+            EncodingTable {
+                aliases: vec![('{', 0, Sign::Positive)],
+                ..Default::default()
+            },
+            '{',
+            "character appears more than once",
+        ),
+        (
+            EncodingTable {
+                aliases: vec![('?', 10, Sign::Positive)],
+                ..Default::default()
+            },
+            '?',
+            "aliases must stand for a digit from 0 to 9",
+        ),
+        (
+            EncodingTable {
+                positive: chars("1023456789"),
+                ..Default::default()
+            },
+            '1',
+            "a digit character must stand for its own digit",
+        ),
+        (
+            EncodingTable {
+                negative: chars("0123456789"),
+                plain_digits: Some(Sign::Positive),
+                ..Default::default()
+            },
+            '0',
+            "conflicts with the sign of plain digits",
+        ),
+    ];
+    for (table, symbol, reason) in cases {
+        assert_eq!(
+            TableEncoding::new(table).unwrap_err(),
+            Error::InvalidEncodingTable { symbol, reason }
+        );
+    }
+}
+
+#[test]
+fn test_table_in_options() {
+    let encoding: &'static TableEncoding = Box::leak(Box::new(
+        TableEncoding::new(EncodingTable {
+            positive: chars("0123456789"),
+            negative: chars("pqrstuvwxy"),
+            ..Default::default()
+        })
+        .unwrap(),
+    ));
+    let format = FormatOptions {
+        encoding,
+        ..Default::default()
+    };
+    let parse = ParseOptions {
+        encoding,
+        ..Default::default()
+    };
+    let formatted = format_with_options(dec("-123.45"), 2, &format).unwrap();
+    assert_eq!(formatted, "1234u");
+    assert_eq!(
+        extract_with_options(&formatted, 2, &parse),
+        Ok(dec("-123.45"))
+    );
+}
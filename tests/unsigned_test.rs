@@ -0,0 +1,171 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::copybook::RecordLayout;
+use overpunch_ng::error::Error;
+use overpunch_ng::record::{FieldValue, Record, RecordDecoder, RecordEncoder};
+use overpunch_ng::{
+    convert_from_signed_format_with_options, convert_to_signed_format_with_options,
+    extract_bytes_with_report, extract_with_options, extract_with_report, packed, ByteEncoding,
+    Ebcdic, Encoding, FormatOptions, ParseOptions, Picture, Sign, UnsignedPolicy, ZonedEbcdic,
+    ZonedSign,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn policy(unsigned: UnsignedPolicy) -> ParseOptions {
+    ParseOptions {
+        unsigned,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_three_state_sign() {
+    assert_eq!(Ebcdic.decode('5'), Ok((5, Sign::Unsigned)));
+    assert_eq!(Ebcdic.decode('E'), Ok((5, Sign::Positive)));
+    assert_eq!(Ebcdic.decode('N'), Ok((5, Sign::Negative)));
+    assert_eq!(Ebcdic.encode(5, Sign::Unsigned), Ok('5'));
+    assert_eq!(ZonedEbcdic.decode_byte(0xF5), Ok((5, Sign::Unsigned)));
+    assert_eq!(ZonedEbcdic.decode_byte(0xC5), Ok((5, Sign::Positive)));
+    assert_eq!(ZonedEbcdic.encode_byte(5, Sign::Unsigned), Ok(0xF5));
+}
+
+#[test]
+fn test_unsigned_policies() {
+    assert_eq!(
+        extract_with_options("1235", 1, &ParseOptions::default()),
+        Ok(dec("123.5"))
+    );
+
+    let reject = policy(UnsignedPolicy::Reject);
+    assert_eq!(
+        extract_with_options("1235", 1, &reject),
+        Err(Error::MissingSign)
+    );
+    assert_eq!(extract_with_options("123E", 1, &reject), Ok(dec("123.5")));
+    assert_eq!(
+        extract_bytes_with_report(&[0xF1, 0xF2], 0, &ZonedEbcdic, &reject),
+        Err(Error::MissingSign)
+    );
+
+    let flag = policy(UnsignedPolicy::Flag);
+    let flagged = extract_with_report("1235", 1, &flag).unwrap();
+    assert_eq!(flagged.value, Some(dec("123.5")));
+    assert!(flagged.adjustments.unsigned);
+    assert!(
+        !extract_with_report("123E", 1, &flag)
+            .unwrap()
+            .adjustments
+            .unsigned
+    );
+    let flagged = extract_bytes_with_report(&[0xF1, 0xF2], 0, &ZonedEbcdic, &flag).unwrap();
+    assert_eq!(flagged.value, Some(dec("12")));
+    assert!(flagged.adjustments.unsigned);
+
+    // Without the flag policy nothing is reported
+    let accepted = extract_with_report("1235", 1, &ParseOptions::default()).unwrap();
+    assert!(accepted.adjustments.is_empty());
+}
+
+#[test]
+fn test_unsigned_pictures_are_exempt() {
+    let reject = policy(UnsignedPolicy::Reject);
+    assert_eq!(
+        convert_from_signed_format_with_options("1235", "9(4)", &reject),
+        Ok(dec("1235"))
+    );
+    assert_eq!(
+        convert_from_signed_format_with_options("1235", "S9(4)", &reject),
+        Err(Error::MissingSign)
+    );
+
+    let layout = RecordLayout::parse("01 R. 05 A PIC S9(2). 05 B PIC 9(2).").unwrap();
+    let decoder = RecordDecoder::with_options(layout, reject);
+    assert_eq!(
+        decoder
+            .decode_bytes(&[0xF1, 0xC2, 0xF3, 0xF4])
+            .unwrap()
+            .get("B"),
+        Some(&FieldValue::Number(dec("34")))
+    );
+    assert!(matches!(
+        decoder.decode_bytes(&[0xF1, 0xF2, 0xF3, 0xF4]),
+        Err(Error::FieldError { source, .. }) if *source == Error::MissingSign
+    ));
+}
+
+#[test]
+fn test_format_unsigned_pictures() {
+    let unsigned = FormatOptions {
+        zoned_sign: ZonedSign::Unsigned,
+        ..Default::default()
+    };
+    let cases = [
+        ("123", "9(4)", "012C", "0123"),
+        ("123", "S9(4)", "012C", "012C"),
+        ("-123", "S9(4)", "012L", "012L"),
+        ("1.5", "9V9", "1E", "15"),
+    ];
+    for (value, picture, preferred, written) in cases {
+        assert_eq!(
+            convert_to_signed_format_with_options(dec(value), picture, &FormatOptions::default()),
+            Ok(preferred.to_string())
+        );
+        assert_eq!(
+            convert_to_signed_format_with_options(dec(value), picture, &unsigned),
+            Ok(written.to_string())
+        );
+    }
+
+    let layout = RecordLayout::parse("01 R. 05 A PIC S9(2). 05 B PIC 9(2).").unwrap();
+    let mut record = Record::new();
+    record.insert("A", FieldValue::Number(dec("12")));
+    record.insert("B", FieldValue::Number(dec("34")));
+    let encoder = RecordEncoder::with_options(layout, unsigned);
+    assert_eq!(
+        encoder.encode_bytes(&record),
+        Ok(vec![0xF1, 0xC2, 0xF3, 0xF4])
+    );
+}
+
+#[test]
+fn test_packed_f_nibble_is_unsigned() {
+    assert_eq!(packed::unpack(&[0x12, 0x3F], 0), Ok(dec("123")));
+    assert_eq!(packed::unpack(&[0x12, 0x3C], 0), Ok(dec("123")));
+
+    let signed = Picture::parse("S9(3) COMP-3").unwrap();
+    let reject = policy(UnsignedPolicy::Reject);
+    assert_eq!(
+        packed::unpack_with_report(&[0x12, 0x3F], &signed, &reject),
+        Err(Error::MissingSign)
+    );
+    assert_eq!(
+        packed::unpack_with_report(&[0x12, 0x3C], &signed, &reject)
+            .unwrap()
+            .value,
+        Some(dec("123"))
+    );
+
+    let flagged =
+        packed::unpack_with_report(&[0x12, 0x3F], &signed, &policy(UnsignedPolicy::Flag)).unwrap();
+    assert_eq!(flagged.value, Some(dec("123")));
+    assert!(flagged.adjustments.unsigned);
+
+    let unsigned = Picture::parse("9(3) COMP-3").unwrap();
+    assert_eq!(
+        packed::unpack_with_report(&[0x12, 0x3F], &unsigned, &reject)
+            .unwrap()
+            .value,
+        Some(dec("123"))
+    );
+
+    let layout = RecordLayout::parse("01 R. 05 A PIC S9(3) COMP-3.").unwrap();
+    assert!(matches!(
+        RecordDecoder::with_options(layout, reject).decode_bytes(&[0x12, 0x3F]),
+        Err(Error::FieldError { source, .. }) if *source == Error::MissingSign
+    ));
+}
@@ -0,0 +1,181 @@
+#![allow(clippy::disallowed_methods)]
+
+use overpunch_ng::error::Error;
+use overpunch_ng::{
+    extract_bytes, extract_bytes_with_encoding, format_into, format_into_with_encoding,
+    ByteEncoding, CodePage, Ebcdic, FormatOptions, OverflowPolicy, ParseOptions, Sign,
+    SignPosition, ZonedEbcdic,
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+#[test]
+fn test_zoned_ebcdic_nibbles() {
+    let encoding = ZonedEbcdic;
+    assert_eq!(encoding.encode_byte(3, Sign::Positive).unwrap(), 0xC3);
+    assert_eq!(encoding.encode_byte(0, Sign::Negative).unwrap(), 0xD0);
+    assert_eq!(encoding.encode_byte(7, Sign::Unsigned).unwrap(), 0xF7);
+    assert_eq!(encoding.encode_digit_byte(7).unwrap(), 0xF7);
+    assert_eq!(encoding.decode_byte(0xC9).unwrap(), (9, Sign::Positive));
+    assert_eq!(encoding.decode_byte(0xD1).unwrap(), (1, Sign::Negative));
+    assert_eq!(encoding.decode_byte(0xF4).unwrap(), (4, Sign::Unsigned));
+    assert_eq!(encoding.decode_byte(0xA2).unwrap(), (2, Sign::Positive));
+    assert_eq!(encoding.decode_byte(0xE5).unwrap(), (5, Sign::Positive));
+    assert_eq!(encoding.decode_byte(0xB6).unwrap(), (6, Sign::Negative));
+    assert_eq!(
+        encoding.decode_byte(0x95),
+        Err(Error::UnsupportedByte(0x95))
+    );
+    assert_eq!(
+        encoding.decode_byte(0xCA),
+        Err(Error::UnsupportedByte(0xCA))
+    );
+    assert_eq!(
+        encoding.decode_digit_byte(0xC1),
+        Err(Error::UnsupportedByte(0xC1))
+    );
+    assert!(encoding.encode_byte(10, Sign::Positive).is_err());
+}
+
+#[test]
+fn test_extract_bytes() {
+    assert_eq!(
+        extract_bytes(&[0xF1, 0xF2, 0xF3, 0xC4], 2).unwrap(),
+        dec("12.34")
+    );
+    assert_eq!(
+        extract_bytes(&[0xF1, 0xF2, 0xF3, 0xD4], 2).unwrap(),
+        dec("-12.34")
+    );
+    assert_eq!(
+        extract_bytes(&[0xF1, 0xF2, 0xF3, 0xF4], 0).unwrap(),
+        dec("1234")
+    );
+    assert_eq!(extract_bytes(&[0xF0, 0xB7], 0).unwrap(), dec("-7"));
+    assert_eq!(extract_bytes(&[], 0), Err(Error::EmptyField));
+    assert_eq!(
+        extract_bytes(&[0xF1, 0xC2, 0xC3], 0),
+        Err(Error::InvalidByte {
+            byte: 0xC2,
+            index: 1
+        })
+    );
+    assert_eq!(
+        extract_bytes(&[0xF1, 0x40], 0),
+        Err(Error::InvalidByte {
+            byte: 0x40,
+            index: 1
+        })
+    );
+}
+
+#[test]
+fn test_format_into() {
+    let mut buf = [0u8; 5];
+    format_into(&mut buf, dec("-12.34"), 2).unwrap();
+    assert_eq!(buf, [0xF0, 0xF1, 0xF2, 0xF3, 0xD4]);
+
+    format_into(&mut buf, dec("7"), 0).unwrap();
+    assert_eq!(buf, [0xF0, 0xF0, 0xF0, 0xF0, 0xC7]);
+
+    let mut narrow = [0u8; 2];
+    assert_eq!(
+        format_into(&mut narrow, dec("123"), 0),
+        Err(Error::FieldTooNarrow {
+            required: 3,
+            available: 2
+        })
+    );
+    assert_eq!(
+        format_into(&mut [], dec("0"), 0),
+        Err(Error::FieldTooNarrow {
+            required: 1,
+            available: 0
+        })
+    );
+
+    let options = FormatOptions {
+        overflow: OverflowPolicy::Truncate,
+        ..FormatOptions::default()
+    };
+    format_into_with_encoding(&mut narrow, dec("-123"), 0, &ZonedEbcdic, &options).unwrap();
+    assert_eq!(narrow, [0xF2, 0xD3]);
+}
+
+#[test]
+fn test_byte_sign_positions() {
+    let parse = ParseOptions {
+        sign_position: SignPosition::LeadingSeparate,
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        extract_bytes_with_encoding(&[0x60, 0xF1, 0xF2], 1, &ZonedEbcdic, &parse).unwrap(),
+        dec("-1.2")
+    );
+
+    let format = FormatOptions {
+        sign_position: SignPosition::TrailingSeparate,
+        ..FormatOptions::default()
+    };
+    let mut buf = [0u8; 4];
+    format_into_with_encoding(&mut buf, dec("12"), 0, &ZonedEbcdic, &format).unwrap();
+    assert_eq!(buf, [0xF0, 0xF1, 0xF2, 0x4E]);
+
+    let format = FormatOptions {
+        sign_position: SignPosition::LeadingOverpunch,
+        ..FormatOptions::default()
+    };
+    format_into_with_encoding(&mut buf, dec("-12"), 0, &ZonedEbcdic, &format).unwrap();
+    assert_eq!(buf, [0xD0, 0xF0, 0xF1, 0xF2]);
+}
+
+#[test]
+fn test_character_encodings_work_on_bytes() {
+    let options = ParseOptions::default();
+    assert_eq!(
+        extract_bytes_with_encoding(b"123J", 2, &Ebcdic, &options).unwrap(),
+        dec("-12.31")
+    );
+
+    let mut buf = [0u8; 6];
+    format_into_with_encoding(
+        &mut buf,
+        dec("12.31"),
+        2,
+        &Ebcdic,
+        &FormatOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(&buf, b"00123A");
+}
+
+#[test]
+fn test_zoned_roundtrip() {
+    for raw in ["0", "-0.01", "999.99", "-123.45", "42"] {
+        let value = dec(raw);
+        let mut buf = [0u8; 7];
+        format_into(&mut buf, value, 2).unwrap();
+        assert_eq!(extract_bytes(&buf, 2).unwrap(), value.round_dp(2));
+    }
+}
+
+#[test]
+fn test_code_pages() {
+    assert_eq!(
+        CodePage::Cp037.decode(&[0xC8, 0x85, 0x93, 0x93, 0x96, 0x40, 0x5B, 0xF1]),
+        "Hello $1"
+    );
+    assert_eq!(CodePage::Latin1.decode(b"abc\xE9"), "abc\u{e9}");
+    for byte in 0..=255u8 {
+        let c = CodePage::Cp037.decode(&[byte]).chars().next().unwrap();
+        assert_eq!(CodePage::Cp037.encode_char(c).unwrap(), byte);
+    }
+    assert_eq!(
+        CodePage::Cp037.encode_char('€'),
+        Err(Error::UnsupportedCharacter('€'))
+    );
+}